@@ -1,24 +1,29 @@
-use ethers_signers::{LocalWallet, Signer};
-use ethers::utils::hex;
-use web3::signing::keccak256;
+use bluefin_signing::arb::onboarding;
+use bluefin_signing::Environment;
+use ethers_signers::LocalWallet;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> bluefin_signing::Result<()> {
 
-    // for testnet this is the message to be signed, for mainnet use https://trade-arb.firefly.exchange
-    let msg = "https://testnet.firefly.exchange";    
+    // Arbitrum testnet unless BLUEFIN_ENV (e.g. arb-mainnet) or BLUEFIN_CONFIG (a JSON file) say otherwise
+    let environment = Environment::from_env(Environment::arb_testnet())?;
+
+    // the onboarding url of the environment is the message to be signed
+    let msg = environment.onboarding_url.as_str();
 
     // take hash of the message
-    let hash = hex::encode(keccak256(msg.as_bytes()));
+    let hash = onboarding::get_onboarding_hash(msg);
     println!("message hash: {}", hash);
 
     const WALLET_KEY: &str = "2ee813034aab842141cb85d477f7d0e359838f46fcab34a935c69410a4d39efb";
 
     let wallet = WALLET_KEY.parse::<LocalWallet>().unwrap();
 
-    let signature = format!("0x{}", wallet.sign_message(hex::decode(&hash).unwrap().as_slice()).await.unwrap().to_string());
+    let signature = onboarding::sign_onboarding_message(&wallet, msg).await?;
 
     // send this signature to /authorize route
     println!("onboarding signature: {}", signature);
 
+    Ok(())
+
 }
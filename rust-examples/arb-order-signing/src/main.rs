@@ -1,19 +1,23 @@
+use bluefin_signing::arb::{api, onboarding, order, signature, BluefinClient};
+use bluefin_signing::{Amount, Environment, OrderType};
 use ethers_signers::{LocalWallet, Signer};
-use web3_unit_converter::Unit;
-
-// module containing order struct and signing functionality
-mod order;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> bluefin_signing::Result<()> {
 
     const WALLET_KEY: &str = "2ee813034aab842141cb85d477f7d0e359838f46fcab34a935c69410a4d39efb";
 
+    // Arbitrum testnet unless BLUEFIN_ENV (e.g. arb-mainnet) or BLUEFIN_CONFIG (a JSON file) say otherwise
+    let environment = Environment::from_env(Environment::arb_testnet())?;
+
+    // Market: ETH-PERP
+    let market = "ETH-PERP";
+
     // for each market there is a different trader contract
-    const TRADER_CONTRACT: &str = "0x934Dd6503795ef6EE6a36e3b3f1d7Be6c7096955"; 
+    let trader_contract = environment.contract(market)?;
 
     // chain id for testnet its 421613, for mainnet its 42161
-    const NETWORK_ID: &str = "421613";
+    let network_id = environment.chain_id()?;
 
 
     let wallet = WALLET_KEY.parse::<LocalWallet>().unwrap();
@@ -24,32 +28,54 @@ async fn main() {
     let order = order::Order {
         is_buy: true,
         reduce_only: true,
-        price: Unit::Ether(&"1800").to_wei_str().unwrap(), // in 1e18 format
-        quantity: Unit::Ether(&"6").to_wei_str().unwrap(), // in 1e18 format
-        leverage: Unit::Ether(&"0.02").to_wei_str().unwrap(), // in 1e18 format
-        trigger_price:  Unit::Ether(&"0").to_wei_str().unwrap(), // in 1e18 format, always zero
-        expiration:"1690995498".to_string(),
-        salt:"1231231231".to_string(),
+        price: "1800".parse()?, // signed in 1e18 format
+        quantity: "6".parse()?, // signed in 1e18 format
+        leverage: "0.02".parse()?, // signed in 1e18 format
+        trigger_price: Amount::ZERO, // always zero
+        expiration: 1690995498,
+        salt: 1231231231,
         maker: address,
     };
 
     println!("{:?}\n", order);
 
-    let order_hash = order::get_hash(order, TRADER_CONTRACT, NETWORK_ID);
+    let order_hash = order::get_hash(&order, trader_contract, network_id)?;
     println!("Order hash: 0x{}\n", order_hash);
 
-    let signature =  order::sign_order(wallet, &order_hash).await;
+    let signature =  order::sign_order(&wallet, &order_hash).await?;
     println!("Order signature: {}\n", signature);
 
+    // the exchange checks that the order was signed by its maker
+    assert!(signature::verify_order_signature(&order, &signature, trader_contract, network_id)?);
+
     let order_hash_0x = "0x".to_string() + &order_hash;
 
-    let cancel_order_hash = order::get_cancel_hash(&order_hash_0x, TRADER_CONTRACT, NETWORK_ID);
+    let cancel_order_hash = order::get_cancel_hash(&[&order_hash_0x], trader_contract, network_id)?;
     println!("Cancel Order hash: 0x{}\n", cancel_order_hash);
 
     // sign cancellation hash
-    let wallet = WALLET_KEY.parse::<LocalWallet>().unwrap();
-    let cancel_signature =  order::sign_order(wallet, &cancel_order_hash).await;
+    let cancel_signature =  order::sign_order(&wallet, &cancel_order_hash).await?;
     println!("Cancel signature: {}\n", cancel_signature);
 
-}
+    assert!(signature::verify_cancel_signature(&[&order_hash_0x], &cancel_signature, address, trader_contract, network_id)?);
 
+    // one client for every request, so the connection is reused
+    let client = BluefinClient::new(environment.clone());
+
+    // sign the onboarding url and obtain the JWT token
+    let onboarding_signature = onboarding::sign_onboarding_message(&wallet, &environment.onboarding_url).await?;
+    let jwt_token = client.authorize(&onboarding_signature, address).await?;
+
+    // Post Order and return the order hash computed by the exchange
+    let order_request = api::OrderJSONRequest::new(market, &order, &signature, OrderType::Limit);
+    let response = client.post_order(&order_request, &jwt_token).await?;
+    println!("Returned Order Hash: {}\n", response.hash);
+
+    // api::create_cancel_requests signs and splits any number of hashes into several requests
+    let cancel_order = api::OrderCancellationJSONRequest::new(market, &[&order_hash_0x], &cancel_signature, None);
+    let response = client.cancel_orders(&cancel_order, &jwt_token).await?;
+    println!("Response: {:?}", response);
+
+    Ok(())
+
+}
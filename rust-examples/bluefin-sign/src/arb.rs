@@ -0,0 +1,462 @@
+use bluefin_signing::arb::signature::parse_signature;
+use bluefin_signing::arb::{
+    api, decode_flags, get_cancel_hash, get_hash, onboarding, recover_signer, sign_order, BluefinClient, Order,
+};
+use bluefin_signing::{Amount, Environment, OrderType, Result, Side, SigningError, TimeInForce};
+use clap::{Args, Subcommand};
+use ethers::types::H160;
+use ethers_signers::{LocalWallet, Signer};
+use serde::{Deserialize, Serialize};
+
+use crate::input::{self, amount, read_hashes, read_json};
+use crate::to_json;
+
+#[derive(Args)]
+pub struct ArbArgs {
+    /// Hex private key of the wallet
+    #[arg(long, env = "BLUEFIN_PRIVATE_KEY", hide_env_values = true, global = true)]
+    key: Option<String>,
+    /// Environment preset, otherwise BLUEFIN_CONFIG or BLUEFIN_ENV, falling back to arb-testnet
+    #[arg(long, global = true)]
+    env: Option<String>,
+    #[command(subcommand)]
+    command: ArbCommand,
+}
+
+#[derive(Args)]
+struct MarketArgs {
+    /// Market symbol, such as ETH-PERP
+    #[arg(long)]
+    market: String,
+    /// Trader contract of the market, by default the one the environment lists for it
+    #[arg(long)]
+    contract: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum ArbCommand {
+    /// Print the wallet address
+    Address,
+    /// Sign the onboarding url of the environment
+    Onboard {
+        /// Also exchange the signature for a JWT token at /authorize
+        #[arg(long)]
+        authorize: bool,
+    },
+    /// Sign an order, printing its hash, signature and the REST payload placing it
+    SignOrder {
+        #[command(flatten)]
+        market: MarketArgs,
+        /// Order as JSON, @file or - for stdin
+        #[arg(long, default_value = "-")]
+        order: String,
+    },
+    /// Sign the cancellation of order hashes, one request per --max-hashes hashes
+    Cancel {
+        #[command(flatten)]
+        market: MarketArgs,
+        /// Order hash to cancel, repeat for several
+        #[arg(long)]
+        hash: Vec<String>,
+        /// JSON array of order hashes, @file or - for stdin
+        #[arg(long)]
+        hashes: Option<String>,
+        /// Account the orders belong to, when a sub-account cancels them
+        #[arg(long)]
+        parent: Option<H160>,
+        #[arg(long, default_value_t = api::MAX_CANCEL_ORDER_HASHES)]
+        max_hashes: usize,
+    },
+    /// Check the signature of an order, or with --hash/--hashes of a cancellation
+    Verify {
+        #[command(flatten)]
+        market: MarketArgs,
+        #[arg(long)]
+        signature: String,
+        /// Order as JSON, @file or - for stdin
+        #[arg(long, required_unless_present_any = ["hash", "hashes"], conflicts_with_all = ["hash", "hashes"])]
+        order: Option<String>,
+        /// Cancelled order hash, repeat for several
+        #[arg(long)]
+        hash: Vec<String>,
+        /// JSON array of cancelled order hashes, @file or - for stdin
+        #[arg(long)]
+        hashes: Option<String>,
+        /// Expected signer of a cancellation, by default the address of --key
+        #[arg(long)]
+        signer: Option<H160>,
+    },
+    /// Decode the bytes8 flags of an order or an exchange signature
+    Decode {
+        #[arg(long, required_unless_present = "signature", conflicts_with = "signature")]
+        flags: Option<String>,
+        #[arg(long)]
+        signature: Option<String>,
+    },
+}
+
+/**
+ * Order parameters, amounts as decimals ("1800.5") rather than in 1e18 format.
+ * The maker defaults to the address of the key
+ */
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct OrderInput {
+    side: Side,
+    #[serde(default)]
+    reduce_only: bool,
+    #[serde(deserialize_with = "amount")]
+    quantity: Amount,
+    #[serde(default = "input::zero", deserialize_with = "amount")]
+    price: Amount,
+    #[serde(default = "input::zero", deserialize_with = "amount")]
+    trigger_price: Amount,
+    #[serde(deserialize_with = "amount")]
+    leverage: Amount,
+    expiration: u128,
+    salt: u128,
+    #[serde(default)]
+    maker: Option<H160>,
+    order_type: OrderType,
+    #[serde(default = "input::gtt")]
+    time_in_force: TimeInForce,
+    #[serde(default)]
+    post_only: bool,
+}
+
+impl OrderInput {
+    fn to_order(&self, args: &ArbArgs) -> Result<Order> {
+        let maker = match self.maker {
+            Some(maker) => maker,
+            None => args.wallet()?.address(),
+        };
+        Ok(Order {
+            is_buy: self.side == Side::Buy,
+            reduce_only: self.reduce_only,
+            quantity: self.quantity,
+            price: self.price,
+            trigger_price: self.trigger_price,
+            leverage: self.leverage,
+            expiration: self.expiration,
+            salt: self.salt,
+            maker,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct AddressOutput {
+    address: H160,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OnboardOutput {
+    address: H160,
+    onboarding_url: String,
+    signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SignOrderOutput {
+    hash: String,
+    signature: String,
+    request: api::OrderJSONRequest,
+}
+
+#[derive(Serialize)]
+struct CancelOutput {
+    requests: Vec<api::OrderCancellationJSONRequest>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VerifyOutput {
+    valid: bool,
+    hash: String,
+    signer: H160,
+    expected_signer: H160,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FlagsOutput {
+    is_buy: bool,
+    reduce_only: bool,
+    salt: u128,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignatureOutput {
+    r: String,
+    s: String,
+    v: u64,
+    signature_type: String,
+}
+
+impl ArbArgs {
+    fn wallet(&self) -> Result<LocalWallet> {
+        let key = self
+            .key
+            .as_deref()
+            .ok_or_else(|| SigningError::Key("no private key, pass --key or set BLUEFIN_PRIVATE_KEY".to_string()))?;
+        key.parse::<LocalWallet>()
+            .map_err(|e| SigningError::Key(format!("invalid wallet key: {}", e)))
+    }
+
+    fn environment(&self) -> Result<Environment> {
+        input::environment(self.env.as_deref(), Environment::arb_testnet())
+    }
+}
+
+impl MarketArgs {
+    /**
+     * Returns the trader contract of the market and the chain id, the EIP-712 domain of its orders
+     */
+    fn domain(&self, environment: &Environment) -> Result<(String, String)> {
+        let trader_contract = match &self.contract {
+            Some(contract) => contract.clone(),
+            None => environment.contract(&self.market)?.to_string(),
+        };
+        Ok((trader_contract, environment.chain_id()?.to_string()))
+    }
+}
+
+pub async fn run(args: ArbArgs) -> Result<String> {
+    match &args.command {
+        ArbCommand::Address => to_json(&AddressOutput {
+            address: args.wallet()?.address(),
+        }),
+        ArbCommand::Onboard { authorize } => {
+            let wallet = args.wallet()?;
+            let environment = args.environment()?;
+            let signature = onboarding::sign_onboarding_message(&wallet, &environment.onboarding_url).await?;
+            let token = if *authorize {
+                Some(BluefinClient::new(environment.clone()).authorize(&signature, wallet.address()).await?)
+            } else {
+                None
+            };
+            to_json(&OnboardOutput {
+                address: wallet.address(),
+                onboarding_url: environment.onboarding_url,
+                signature,
+                token,
+            })
+        }
+        ArbCommand::SignOrder { market, order } => {
+            let wallet = args.wallet()?;
+            let (trader_contract, chain_id) = market.domain(&args.environment()?)?;
+            let input: OrderInput = read_json(order)?;
+            let order = input.to_order(&args)?;
+
+            let order_hash = get_hash(&order, &trader_contract, &chain_id)?;
+            let signature = sign_order(&wallet, &order_hash).await?;
+
+            let mut request = api::OrderJSONRequest::new(&market.market, &order, &signature, input.order_type);
+            request.time_in_force = input.time_in_force;
+            request.post_only = input.post_only;
+            request.validate()?;
+            to_json(&SignOrderOutput {
+                hash: format!("0x{}", order_hash),
+                signature,
+                request,
+            })
+        }
+        ArbCommand::Cancel {
+            market,
+            hash,
+            hashes,
+            parent,
+            max_hashes,
+        } => {
+            let wallet = args.wallet()?;
+            let (trader_contract, chain_id) = market.domain(&args.environment()?)?;
+            let order_hashes = read_hashes(hash, hashes.as_deref())?;
+            let order_hashes: Vec<&str> = order_hashes.iter().map(String::as_str).collect();
+            let requests = api::create_cancel_requests(
+                &wallet,
+                &market.market,
+                &order_hashes,
+                &trader_contract,
+                &chain_id,
+                *parent,
+                *max_hashes,
+            )
+            .await?;
+            to_json(&CancelOutput { requests })
+        }
+        ArbCommand::Verify {
+            market,
+            signature,
+            order,
+            hash,
+            hashes,
+            signer,
+        } => {
+            let (trader_contract, chain_id) = market.domain(&args.environment()?)?;
+            let (signed_hash, expected_signer) = match order {
+                Some(order) => {
+                    let order = read_json::<OrderInput>(order)?.to_order(&args)?;
+                    (get_hash(&order, &trader_contract, &chain_id)?, order.maker)
+                }
+                None => {
+                    let expected_signer = match signer {
+                        Some(signer) => *signer,
+                        None => args.wallet()?.address(),
+                    };
+                    let order_hashes = read_hashes(hash, hashes.as_deref())?;
+                    (get_cancel_hash(&order_hashes, &trader_contract, &chain_id)?, expected_signer)
+                }
+            };
+            let recovered_signer = recover_signer(&signed_hash, signature)?;
+            to_json(&VerifyOutput {
+                valid: recovered_signer == expected_signer,
+                hash: format!("0x{}", signed_hash),
+                signer: recovered_signer,
+                expected_signer,
+            })
+        }
+        ArbCommand::Decode { flags, signature } => match (flags, signature) {
+            (Some(flags), _) => {
+                let flags: [u8; 8] = hex::decode(flags.trim_start_matches("0x"))
+                    .map_err(|e| SigningError::InvalidInput(format!("flags are not valid hex: {}", e)))?
+                    .try_into()
+                    .map_err(|_| SigningError::InvalidInput("flags must be 8 bytes".to_string()))?;
+                let flags = decode_flags(flags)?;
+                to_json(&FlagsOutput {
+                    is_buy: flags.is_buy,
+                    reduce_only: flags.reduce_only,
+                    salt: flags.salt,
+                })
+            }
+            (None, Some(signature)) => {
+                let (signature, signature_type) = parse_signature(signature)?;
+                to_json(&SignatureOutput {
+                    r: format!("{:#066x}", signature.r),
+                    s: format!("{:#066x}", signature.s),
+                    v: signature.v,
+                    signature_type: format!("{:?}", signature_type),
+                })
+            }
+            (None, None) => Err(SigningError::InvalidInput("pass --flags or --signature".to_string())),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::tests::{keys, run_cli};
+
+    const KEY: &str = "2ee813034aab842141cb85d477f7d0e359838f46fcab34a935c69410a4d39efb";
+
+    const ADDRESS: &str = "0x6f03f28bc1ebb7c9b45614bf2483e70f008a6d3d";
+
+    /**
+     * The order of the arb-order-signing example
+     */
+    const ORDER: &str = r#"{"side": "BUY", "reduceOnly": true, "quantity": "6", "price": "1800", "leverage": "0.02", "expiration": 1690995498, "salt": 1231231231, "orderType": "LIMIT"}"#;
+
+    const ORDER_HASH: &str = "0x400fbdbc69662f7bf23c57799ea6b5ff6c6c7e2742a46eccbf83b3366595bf43";
+
+    const SIGNATURE: &str = "0x2e2dbad4610bc052a81eb6b7791882ece65b7137190877cf7f9e958444d7057420eaef3fc56749c6863a55f65b7a5b27278708ad96d89fb695c4fd1998d994401b01";
+
+    async fn arb(args: &[&str]) -> bluefin_signing::Result<Value> {
+        run_cli(&[&["arb", "--key", KEY, "--env", "arb-testnet"], args].concat()).await
+    }
+
+    #[tokio::test]
+    async fn sign_order_prints_the_hash_signature_and_request() {
+        let output = arb(&["sign-order", "--market", "ETH-PERP", "--order", ORDER]).await.unwrap();
+
+        assert_eq!(keys(&output), ["hash", "request", "signature"]);
+        assert_eq!(output["hash"], ORDER_HASH);
+        assert_eq!(output["signature"], SIGNATURE);
+        assert_eq!(
+            output["request"],
+            json!({
+                "symbol": "ETH-PERP",
+                "userAddress": ADDRESS,
+                "orderType": "LIMIT",
+                "price": "1800000000000000000000",
+                "triggerPrice": "0",
+                "quantity": "6000000000000000000",
+                "leverage": "20000000000000000",
+                "side": "BUY",
+                "reduceOnly": true,
+                "salt": 1231231231,
+                "expiration": 1690995498,
+                "orderSignature": SIGNATURE,
+                "timeInForce": "GTT",
+                "postOnly": false,
+                "cancelOnRevert": false,
+                "clientId": "bluefin-signing",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn sign_order_refuses_invalid_orders() {
+        let stop_limit = ORDER.replace("LIMIT", "STOP_LIMIT");
+        assert!(arb(&["sign-order", "--market", "ETH-PERP", "--order", &stop_limit]).await.is_err());
+        assert!(arb(&["sign-order", "--market", "BTC-PERP", "--order", ORDER]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn cancel_prints_one_signed_request_per_chunk() {
+        let other_hash = format!("0x{}", "11".repeat(32));
+        let output = arb(&["cancel", "--market", "ETH-PERP", "--hash", ORDER_HASH, "--hash", &other_hash, "--max-hashes", "1"])
+            .await
+            .unwrap();
+
+        assert_eq!(keys(&output), ["requests"]);
+        let requests = output["requests"].as_array().unwrap();
+        assert_eq!(requests.len(), 2);
+        for (request, hash) in requests.iter().zip([ORDER_HASH, &other_hash]) {
+            assert_eq!(keys(request), ["cancelSignature", "orderHashes", "parentAddress", "symbol"]);
+            assert_eq!(request["symbol"], "ETH-PERP");
+            assert_eq!(request["orderHashes"], json!([hash]));
+            assert_eq!(request["parentAddress"], "");
+
+            let signature = request["cancelSignature"].as_str().unwrap();
+            let output = arb(&["verify", "--market", "ETH-PERP", "--signature", signature, "--hash", hash]).await.unwrap();
+            assert_eq!(output["valid"], true);
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_prints_the_signed_hash_and_both_signers() {
+        let output = arb(&["verify", "--market", "ETH-PERP", "--signature", SIGNATURE, "--order", ORDER]).await.unwrap();
+        assert_eq!(
+            output,
+            json!({"valid": true, "hash": ORDER_HASH, "signer": ADDRESS, "expectedSigner": ADDRESS})
+        );
+
+        let other_price = ORDER.replace("1800", "1801");
+        let output = arb(&["verify", "--market", "ETH-PERP", "--signature", SIGNATURE, "--order", &other_price]).await.unwrap();
+        assert_eq!(output["valid"], false);
+        assert_ne!(output["signer"], ADDRESS);
+    }
+
+    #[tokio::test]
+    async fn decode_prints_flags_and_signature_parts() {
+        let output = arb(&["decode", "--flags", "0x0000000496318ff3"]).await.unwrap();
+        assert_eq!(output, json!({"isBuy": true, "reduceOnly": true, "salt": 1231231231}));
+        assert!(arb(&["decode", "--flags", "0x00000000496318ff"]).await.is_err());
+
+        let output = arb(&["decode", "--signature", SIGNATURE]).await.unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "r": "0x2e2dbad4610bc052a81eb6b7791882ece65b7137190877cf7f9e958444d70574",
+                "s": "0x20eaef3fc56749c6863a55f65b7a5b27278708ad96d89fb695c4fd1998d99440",
+                "v": 27,
+                "signatureType": "Decimal",
+            })
+        );
+    }
+}
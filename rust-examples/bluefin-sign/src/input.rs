@@ -0,0 +1,89 @@
+use bluefin_signing::{Amount, Environment, Result, SigningError, TimeInForce};
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::fs;
+use std::io::Read;
+
+/**
+ * Reads a JSON parameter given on the command line: inline JSON, `@path` for a file or `-` for stdin
+ */
+pub fn read_json<T: DeserializeOwned>(arg: &str) -> Result<T> {
+    let json = if arg == "-" {
+        let mut json = String::new();
+        std::io::stdin()
+            .read_to_string(&mut json)
+            .map_err(|e| SigningError::InvalidInput(format!("can not read stdin: {}", e)))?;
+        json
+    } else if let Some(path) = arg.strip_prefix('@') {
+        fs::read_to_string(path).map_err(|e| SigningError::InvalidInput(format!("can not read {}: {}", path, e)))?
+    } else {
+        arg.to_string()
+    };
+    Ok(serde_json::from_str(&json)?)
+}
+
+/**
+ * Order hashes from repeated `--hash` flags, or else from a JSON array given with `--hashes`
+ */
+pub fn read_hashes(hash: &[String], hashes: Option<&str>) -> Result<Vec<String>> {
+    match hashes {
+        Some(hashes) if hash.is_empty() => read_json(hashes),
+        Some(_) => Err(SigningError::InvalidInput("use either --hash or --hashes, not both".to_string())),
+        None if hash.is_empty() => Err(SigningError::InvalidInput("at least one order hash is required".to_string())),
+        None => Ok(hash.to_vec()),
+    }
+}
+
+/**
+ * The `--env` preset if given, otherwise the deployment selected by the BLUEFIN_* environment variables
+ */
+pub fn environment(name: Option<&str>, default: Environment) -> Result<Environment> {
+    match name {
+        Some(name) => Environment::preset(name),
+        None => Environment::from_env(default),
+    }
+}
+
+/**
+ * Reads an amount written as a decimal string ("1800.5"), not in 1e18 format. JSON numbers are refused:
+ * they are read as f64, which silently rounds amounts with more than 15 or so significant digits
+ */
+pub fn amount<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Amount, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::String(amount) => amount.parse().map_err(D::Error::custom),
+        Value::Number(amount) => Err(D::Error::custom(format!(
+            "amounts are decimal strings, write \"{}\" rather than {}",
+            amount, amount
+        ))),
+        other => Err(D::Error::custom(format!("expected a decimal amount, got {}", other))),
+    }
+}
+
+pub fn zero() -> Amount {
+    Amount::ZERO
+}
+
+pub fn gtt() -> TimeInForce {
+    TimeInForce::Gtt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Price {
+        #[serde(deserialize_with = "amount")]
+        price: Amount,
+    }
+
+    #[test]
+    fn amounts_are_decimal_strings() {
+        let price: Price = serde_json::from_str(r#"{"price": "1800.000000000000000001"}"#).unwrap();
+        assert_eq!(price.price.wei(), 1_800_000_000_000_000_000_001);
+        for json in [r#"{"price": 1800}"#, r#"{"price": 1800.5}"#, r#"{"price": null}"#, r#"{"price": "1e3"}"#] {
+            assert!(serde_json::from_str::<Price>(json).is_err(), "{}", json);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! `bluefin-sign`: onboarding, order, cancel and verification signing for Bluefin on Arbitrum and Sui.
+//!
+//! Every subcommand prints one JSON object on stdout. Failures print `{"error": "..."}` on stderr
+//! and exit with status 1, so the tool can be scripted without parsing human readable text.
+
+mod arb;
+mod input;
+mod sui;
+
+use bluefin_signing::Result;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use serde_json::json;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "bluefin-sign", version, about = "Sign onboarding messages, orders and cancels for Bluefin")]
+struct Cli {
+    #[command(subcommand)]
+    exchange: Exchange,
+}
+
+#[derive(Subcommand)]
+enum Exchange {
+    /// Firefly/Bluefin on Arbitrum, EIP-712 signatures of an Ethereum wallet
+    Arb(arb::ArbArgs),
+    /// Bluefin on Sui, Ed25519, secp256k1 or secp256r1 signatures of a Sui account
+    Sui(sui::SuiArgs),
+}
+
+/**
+ * Renders the output of a command. Outputs are serialized to text directly: a `serde_json::Value`
+ * can not hold the u128 salts and expirations of orders above u64::MAX
+ */
+fn to_json<T: Serialize>(output: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(output)?)
+}
+
+async fn run(cli: Cli) -> Result<String> {
+    match cli.exchange {
+        Exchange::Arb(args) => arb::run(args).await,
+        Exchange::Sui(args) => sui::run(args).await,
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", json!({ "error": e.to_string() }));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    /**
+     * Runs a command line the way the binary does, returning its JSON output
+     */
+    pub(crate) async fn run_cli(args: &[&str]) -> Result<Value> {
+        let cli = Cli::try_parse_from([&["bluefin-sign"], args].concat()).expect("valid command line");
+        Ok(serde_json::from_str(&run(cli).await?)?)
+    }
+
+    /**
+     * Field names of a JSON object, sorted
+     */
+    pub(crate) fn keys(value: &Value) -> Vec<&str> {
+        let mut keys: Vec<&str> = value.as_object().expect("a JSON object").keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    #[derive(Serialize)]
+    struct Salt {
+        salt: u128,
+    }
+
+    #[test]
+    fn outputs_hold_u128_values() {
+        let json = to_json(&Salt { salt: u128::MAX }).unwrap();
+        assert_eq!(json, "{\n  \"salt\": 340282366920938463463374607431768211455\n}");
+    }
+}
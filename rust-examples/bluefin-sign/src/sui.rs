@@ -0,0 +1,632 @@
+use bluefin_signing::sui::order::{deserialize_order, get_order_hash, get_serialized_order, sign_order};
+use bluefin_signing::sui::{
+    api, onboarding, verify_sui_cancel_signature, verify_sui_order_signature, AuthSession, BluefinClient,
+    BluefinSuiSignature, MarketRegistry, Order, SignatureScheme, SuiAddress, SuiKeyPair,
+};
+use bluefin_signing::{Amount, Environment, OrderType, Result, Side, SigningError, TimeInForce};
+use clap::{Args, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::input::{self, amount, read_hashes, read_json};
+use crate::to_json;
+
+#[derive(Args)]
+pub struct SuiArgs {
+    /// Hex private key of the account
+    #[arg(long, env = "BLUEFIN_PRIVATE_KEY", hide_env_values = true, global = true)]
+    key: Option<String>,
+    /// Signature scheme of the key. The exchange accepts Ed25519 and secp256k1 signatures of orders, cancels and onboarding
+    #[arg(long, value_enum, default_value_t = Scheme::Ed25519, global = true)]
+    scheme: Scheme,
+    /// Environment preset, otherwise BLUEFIN_CONFIG or BLUEFIN_ENV, falling back to sui-staging
+    #[arg(long, global = true)]
+    env: Option<String>,
+    #[command(subcommand)]
+    command: SuiCommand,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Scheme {
+    Ed25519,
+    Secp256k1,
+    Secp256r1,
+}
+
+impl From<Scheme> for SignatureScheme {
+    fn from(scheme: Scheme) -> Self {
+        match scheme {
+            Scheme::Ed25519 => SignatureScheme::Ed25519,
+            Scheme::Secp256k1 => SignatureScheme::Secp256k1,
+            Scheme::Secp256r1 => SignatureScheme::Secp256r1,
+        }
+    }
+}
+
+#[derive(Args)]
+struct MarketsArgs {
+    /// JSON file of market perpetual ids, such as {"ETH-PERP": "0x..."}
+    #[arg(long)]
+    markets: Option<PathBuf>,
+    /// Perpetual id of the market of the order, instead of --markets
+    #[arg(long)]
+    market_id: Option<SuiAddress>,
+}
+
+#[derive(Subcommand)]
+enum SuiCommand {
+    /// Print the account address and public key
+    Address,
+    /// Sign the onboarding url of the environment
+    Onboard {
+        /// Also exchange the signature for a JWT token at /authorize
+        #[arg(long)]
+        authorize: bool,
+        /// File the token is kept in, reused while it belongs to the account and has not expired
+        #[arg(long, requires = "authorize")]
+        token_file: Option<PathBuf>,
+    },
+    /// Sign an order, printing its serialized form, hash, signature and the REST payload placing it.
+    /// The perpetual id of the market is fetched from the exchange unless --markets or --market-id give it
+    SignOrder {
+        #[command(flatten)]
+        markets: MarketsArgs,
+        /// Order as JSON, @file or - for stdin
+        #[arg(long, default_value = "-")]
+        order: String,
+    },
+    /// Sign the cancellation of order hashes, one request per --max-hashes hashes
+    Cancel {
+        /// Market symbol, such as ETH-PERP
+        #[arg(long)]
+        market: String,
+        /// Order hash to cancel, repeat for several
+        #[arg(long)]
+        hash: Vec<String>,
+        /// JSON array of order hashes, @file or - for stdin
+        #[arg(long)]
+        hashes: Option<String>,
+        /// Account the orders belong to, when a sub-account cancels them
+        #[arg(long)]
+        parent: Option<SuiAddress>,
+        #[arg(long, default_value_t = api::MAX_CANCEL_ORDER_HASHES)]
+        max_hashes: usize,
+    },
+    /// Check the signature of an order, or with --hash/--hashes of a cancellation
+    Verify {
+        /// Signature in the exchange wire format or the standard Sui format
+        #[arg(long)]
+        signature: String,
+        /// Order as JSON, @file or - for stdin
+        #[arg(long, required_unless_present_any = ["hash", "hashes"], conflicts_with_all = ["hash", "hashes"])]
+        order: Option<String>,
+        #[command(flatten)]
+        markets: MarketsArgs,
+        /// Cancelled order hash, repeat for several
+        #[arg(long)]
+        hash: Vec<String>,
+        /// JSON array of cancelled order hashes, @file or - for stdin
+        #[arg(long)]
+        hashes: Option<String>,
+        /// Expected signer of a cancellation, by default the address of --key
+        #[arg(long)]
+        signer: Option<SuiAddress>,
+    },
+    /// Decode a serialized order or a signature
+    Decode {
+        /// Hex serialized order
+        #[arg(long, required_unless_present = "signature", conflicts_with = "signature")]
+        order: Option<String>,
+        /// JSON file of market perpetual ids, to report the market of the order by symbol
+        #[arg(long)]
+        markets: Option<PathBuf>,
+        /// Symbol of the market of the order, when --markets does not list its perpetual id
+        #[arg(long, requires = "order")]
+        market: Option<String>,
+        /// Signature in the exchange wire format or the standard Sui format
+        #[arg(long)]
+        signature: Option<String>,
+    },
+}
+
+/**
+ * Order parameters, amounts as decimals ("0.01") rather than in 1e18 format.
+ * The maker defaults to the address of the key
+ */
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct OrderInput {
+    market: String,
+    side: Side,
+    #[serde(deserialize_with = "amount")]
+    quantity: Amount,
+    #[serde(default = "input::zero", deserialize_with = "amount")]
+    price: Amount,
+    #[serde(default = "input::zero", deserialize_with = "amount")]
+    trigger_price: Amount,
+    #[serde(deserialize_with = "amount")]
+    leverage: Amount,
+    expiration: u128,
+    salt: u128,
+    #[serde(default)]
+    maker: Option<SuiAddress>,
+    #[serde(default)]
+    reduce_only: bool,
+    #[serde(default)]
+    post_only: bool,
+    #[serde(default = "orderbook_only")]
+    orderbook_only: bool,
+    order_type: OrderType,
+    #[serde(default = "input::gtt")]
+    time_in_force: TimeInForce,
+}
+
+/**
+ * Orders stay on the orderbook unless asked otherwise, like in the exchange clients
+ */
+fn orderbook_only() -> bool {
+    true
+}
+
+impl OrderInput {
+    fn to_order(&self, args: &SuiArgs) -> Result<Order> {
+        let maker = match self.maker {
+            Some(maker) => maker,
+            None => args.keypair()?.address(),
+        };
+        Ok(Order {
+            market: self.market.clone(),
+            price: self.price.wei(),
+            trigger_price: self.trigger_price.wei(),
+            side: self.side,
+            reduce_only: self.reduce_only,
+            quantity: self.quantity.wei(),
+            post_only: self.post_only,
+            orderbook_only: self.orderbook_only,
+            leverage: self.leverage.wei(),
+            expiration: self.expiration,
+            salt: self.salt,
+            maker,
+            ioc: self.time_in_force == TimeInForce::Ioc,
+            order_type: self.order_type,
+            time_in_force: self.time_in_force,
+        })
+    }
+}
+
+/**
+ * An order in the shape `OrderInput` reads, so a decoded order can be signed again
+ */
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderOutput {
+    market: String,
+    side: Side,
+    quantity: String,
+    price: String,
+    trigger_price: String,
+    leverage: String,
+    expiration: u128,
+    salt: u128,
+    maker: SuiAddress,
+    reduce_only: bool,
+    post_only: bool,
+    orderbook_only: bool,
+    order_type: OrderType,
+    time_in_force: TimeInForce,
+}
+
+impl From<Order> for OrderOutput {
+    fn from(order: Order) -> Self {
+        OrderOutput {
+            market: order.market,
+            side: order.side,
+            quantity: Amount::from_wei(order.quantity).to_string(),
+            price: Amount::from_wei(order.price).to_string(),
+            trigger_price: Amount::from_wei(order.trigger_price).to_string(),
+            leverage: Amount::from_wei(order.leverage).to_string(),
+            expiration: order.expiration,
+            salt: order.salt,
+            maker: order.maker,
+            reduce_only: order.reduce_only,
+            post_only: order.post_only,
+            orderbook_only: order.orderbook_only,
+            order_type: order.order_type,
+            time_in_force: order.time_in_force,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddressOutput {
+    address: SuiAddress,
+    scheme: String,
+    public_key: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OnboardOutput {
+    address: SuiAddress,
+    onboarding_url: String,
+    signature: String,
+    #[serde(flatten)]
+    authorization: Option<AuthorizationOutput>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthorizationOutput {
+    token: String,
+    expires_at: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignOrderOutput {
+    serialized_order: String,
+    hash: String,
+    signature: String,
+    request: api::OrderJSONRequest,
+}
+
+#[derive(Serialize)]
+struct CancelOutput {
+    requests: Vec<api::OrderCancellationJSONRequest>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VerifyOutput {
+    valid: bool,
+    signer: SuiAddress,
+    expected_signer: SuiAddress,
+}
+
+/**
+ * A decoded serialized order: its hash, the perpetual id of its market, and the order itself in the shape
+ * `sign-order` reads, with the market by symbol
+ */
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DecodeOrderOutput {
+    hash: String,
+    market_id: SuiAddress,
+    order: OrderOutput,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignatureOutput {
+    scheme: String,
+    signer: SuiAddress,
+    public_key: String,
+    /// none for secp256r1, the exchange wire format has no signer type for it
+    wire: Option<String>,
+    sui: String,
+}
+
+impl From<&BluefinSuiSignature> for SignatureOutput {
+    fn from(signature: &BluefinSuiSignature) -> Self {
+        SignatureOutput {
+            scheme: format!("{:?}", signature.scheme()),
+            signer: signature.signer_address(),
+            public_key: format!("0x{}", hex::encode(signature.public_key())),
+            wire: signature.to_wire_format().ok(),
+            sui: signature.to_sui_format(),
+        }
+    }
+}
+
+impl SuiArgs {
+    fn keypair(&self) -> Result<SuiKeyPair> {
+        let key = self
+            .key
+            .as_deref()
+            .ok_or_else(|| SigningError::Key("no private key, pass --key or set BLUEFIN_PRIVATE_KEY".to_string()))?;
+        SuiKeyPair::from_hex(self.scheme.into(), key)
+    }
+
+    fn environment(&self) -> Result<Environment> {
+        input::environment(self.env.as_deref(), Environment::sui_staging())
+    }
+}
+
+impl MarketsArgs {
+    /**
+     * Returns a registry holding the perpetual id of the market, from the flags or else from the exchange
+     */
+    async fn registry(&self, market: &str, args: &SuiArgs) -> Result<MarketRegistry> {
+        let mut registry = match &self.markets {
+            Some(path) => MarketRegistry::load(path)?,
+            None => MarketRegistry::new(),
+        };
+        if let Some(market_id) = self.market_id {
+            registry.insert(market, market_id);
+        }
+        if registry.get_market_id(market).is_err() {
+            registry.refresh(&BluefinClient::new(args.environment()?), market).await?;
+        }
+        Ok(registry)
+    }
+}
+
+pub async fn run(args: SuiArgs) -> Result<String> {
+    match &args.command {
+        SuiCommand::Address => {
+            let keypair = args.keypair()?;
+            to_json(&AddressOutput {
+                address: keypair.address(),
+                scheme: format!("{:?}", keypair.scheme()),
+                public_key: format!("0x{}", hex::encode(keypair.public_key())),
+            })
+        }
+        SuiCommand::Onboard { authorize, token_file } => {
+            let keypair = args.keypair()?;
+            let environment = args.environment()?;
+            let signature = onboarding::create_onboarding_signature(&keypair, &environment.onboarding_url)?;
+            let authorization = if *authorize {
+                let mut session = AuthSession::new(BluefinClient::new(environment.clone()), keypair.clone());
+                if let Some(token_file) = token_file {
+                    session = session.with_token_file(token_file);
+                }
+                let token = session.token().await?;
+                Some(AuthorizationOutput {
+                    token,
+                    expires_at: session.current_token().and_then(|token| token.expires_at),
+                })
+            } else {
+                None
+            };
+            to_json(&OnboardOutput {
+                address: keypair.address(),
+                onboarding_url: environment.onboarding_url,
+                signature: signature.to_wire_format()?,
+                authorization,
+            })
+        }
+        SuiCommand::SignOrder { markets, order } => {
+            let keypair = args.keypair()?;
+            let order = read_json::<OrderInput>(order)?.to_order(&args)?;
+            let registry = markets.registry(&order.market, &args).await?;
+
+            let serialized_order = get_serialized_order(&order, &registry)?;
+            let signature = sign_order(&keypair, &serialized_order)?;
+            to_json(&SignOrderOutput {
+                hash: get_order_hash(&serialized_order)?,
+                signature: signature.to_wire_format()?,
+                request: api::OrderJSONRequest::new(&order, &signature)?,
+                serialized_order,
+            })
+        }
+        SuiCommand::Cancel {
+            market,
+            hash,
+            hashes,
+            parent,
+            max_hashes,
+        } => {
+            let keypair = args.keypair()?;
+            let order_hashes = read_hashes(hash, hashes.as_deref())?;
+            let order_hashes: Vec<&str> = order_hashes.iter().map(String::as_str).collect();
+            let requests = api::create_cancel_requests(&keypair, market, &order_hashes, parent.as_ref(), *max_hashes)?;
+            to_json(&CancelOutput { requests })
+        }
+        SuiCommand::Verify {
+            signature,
+            order,
+            markets,
+            hash,
+            hashes,
+            signer,
+        } => {
+            let signature: BluefinSuiSignature = signature.parse()?;
+            let (valid, expected_signer) = match order {
+                Some(order) => {
+                    let order = read_json::<OrderInput>(order)?.to_order(&args)?;
+                    let registry = markets.registry(&order.market, &args).await?;
+                    (verify_sui_order_signature(&order, &signature, &registry)?, order.maker)
+                }
+                None => {
+                    let expected_signer = match signer {
+                        Some(signer) => *signer,
+                        None => args.keypair()?.address(),
+                    };
+                    let order_hashes = read_hashes(hash, hashes.as_deref())?;
+                    let order_hashes: Vec<&str> = order_hashes.iter().map(String::as_str).collect();
+                    (verify_sui_cancel_signature(&order_hashes, &signature, &expected_signer), expected_signer)
+                }
+            };
+            to_json(&VerifyOutput {
+                valid,
+                signer: signature.signer_address(),
+                expected_signer,
+            })
+        }
+        SuiCommand::Decode {
+            order,
+            markets,
+            market,
+            signature,
+        } => match (order, signature) {
+            (Some(order), _) => {
+                let registry = match markets {
+                    Some(path) => MarketRegistry::load(path)?,
+                    None => MarketRegistry::new(),
+                };
+                let mut decoded = deserialize_order(order, &registry)?;
+                // an order of a market the registry does not list decodes to the perpetual id, which sign-order can not read
+                let market_id = match registry.get_market_id(&decoded.market) {
+                    Ok(market_id) => market_id,
+                    Err(_) => {
+                        let market_id: SuiAddress = decoded.market.parse()?;
+                        decoded.market = market.clone().ok_or_else(|| {
+                            SigningError::InvalidInput(format!(
+                                "market {} is not in --markets, pass --market with its symbol",
+                                market_id
+                            ))
+                        })?;
+                        market_id
+                    }
+                };
+                to_json(&DecodeOrderOutput {
+                    hash: get_order_hash(order)?,
+                    market_id,
+                    order: decoded.into(),
+                })
+            }
+            (None, Some(signature)) => to_json(&SignatureOutput::from(&signature.parse::<BluefinSuiSignature>()?)),
+            (None, None) => Err(SigningError::InvalidInput("pass --order or --signature".to_string())),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use std::fs;
+
+    use crate::tests::{keys, run_cli};
+
+    const KEY: &str = "c501312ca9eb1aaac6344edbe160e41d3d8d79570e6440f2a84f7d9abf462270";
+
+    const ADDRESS: &str = "0xc6c71c996d437eb6589d1b8b17afcd1480afd5f30f6b7155ef468a9713d3240e";
+
+    /**
+     * The order of the sui-order-signing example, with ETH-PERP at id 0x3
+     */
+    const ORDER: &str = r#"{"market": "ETH-PERP", "side": "BUY", "quantity": "0.01", "leverage": "3", "expiration": 1696489993397, "salt": 1695466663327515, "orderType": "MARKET"}"#;
+
+    const SERIALIZED_ORDER: &str = "000000000000000000000000000000000000000000000000002386f26fc10000000000000000000029a2241af62c00000000000000000000000606049832371b0000018afeaef4b5c6c71c996d437eb6589d1b8b17afcd1480afd5f30f6b7155ef468a9713d3240e000000000000000000000000000000000000000000000000000000000000000318426c756566696e";
+
+    const ORDER_HASH: &str = "8f4350a82739c4c5d6528dae76df99da64fc68084248786376cd1dbcafddd91b";
+
+    const SIGNATURE: &str = "d65d3ed3ac194729ad9ca6c78e324a8a01b0076953f794a87959109656461260cdae7349813da8c004a59416661c1f52bcae0dfa41c23d9db5ed1989f458ac0d1kVmSBjFwjtxdymQhJJgD63QpO7BkbMfHtzJIUAjovV0=";
+
+    async fn sui(args: &[&str]) -> bluefin_signing::Result<Value> {
+        run_cli(&[&["sui", "--key", KEY, "--env", "sui-staging"], args].concat()).await
+    }
+
+    #[tokio::test]
+    async fn sign_order_prints_the_serialized_order_hash_signature_and_request() {
+        let output = sui(&["sign-order", "--market-id", "0x3", "--order", ORDER]).await.unwrap();
+
+        assert_eq!(keys(&output), ["hash", "request", "serializedOrder", "signature"]);
+        assert_eq!(output["serializedOrder"], SERIALIZED_ORDER);
+        assert_eq!(output["hash"], ORDER_HASH);
+        assert_eq!(output["signature"], SIGNATURE);
+        assert_eq!(
+            output["request"],
+            json!({
+                "orderbookOnly": true,
+                "symbol": "ETH-PERP",
+                "price": "0",
+                "quantity": "10000000000000000",
+                "triggerPrice": "0",
+                "leverage": "3000000000000000000",
+                "userAddress": ADDRESS,
+                "orderType": "MARKET",
+                "side": "BUY",
+                "reduceOnly": false,
+                "salt": 1695466663327515u64,
+                "expiration": 1696489993397u64,
+                "orderSignature": SIGNATURE,
+                "timeInForce": "GTT",
+                "postOnly": false,
+                "cancelOnRevert": false,
+                "clientId": "bluefin-v2-client-python",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn sign_order_signs_secp256k1_orders_for_the_exchange() {
+        let output = sui(&["--scheme", "secp256k1", "sign-order", "--market-id", "0x3", "--order", ORDER]).await.unwrap();
+        let signature = output["signature"].as_str().unwrap();
+        assert_eq!(signature.chars().nth(128), Some('2'));
+        assert_eq!(output["request"]["orderSignature"], signature);
+
+        // the maker defaults to the address of the key, the secp256k1 account here
+        let verify = ["--scheme", "secp256k1", "verify", "--signature", signature, "--market-id", "0x3", "--order", ORDER];
+        assert_eq!(sui(&verify).await.unwrap()["valid"], true);
+        let output = sui(&["verify", "--signature", signature, "--market-id", "0x3", "--order", ORDER]).await.unwrap();
+        assert_eq!(output["valid"], false);
+    }
+
+    #[tokio::test]
+    async fn cancel_prints_one_signed_request_per_chunk() {
+        let output = sui(&["cancel", "--market", "ETH-PERP", "--hash", "0x1", "--hash", "0x2", "--hash", "0x3", "--max-hashes", "2"])
+            .await
+            .unwrap();
+
+        assert_eq!(keys(&output), ["requests"]);
+        let requests = output["requests"].as_array().unwrap();
+        assert_eq!(requests.len(), 2);
+        for (request, hashes) in requests.iter().zip([json!(["0x1", "0x2"]), json!(["0x3"])]) {
+            assert_eq!(keys(request), ["cancelSignature", "orderHashes", "parentAddress", "symbol"]);
+            assert_eq!(request["symbol"], "ETH-PERP");
+            assert_eq!(request["orderHashes"], hashes);
+            assert_eq!(request["parentAddress"], "");
+
+            let mut args = vec!["verify", "--signature", request["cancelSignature"].as_str().unwrap()];
+            for hash in request["orderHashes"].as_array().unwrap() {
+                args.extend(["--hash", hash.as_str().unwrap()]);
+            }
+            assert_eq!(sui(&args).await.unwrap()["valid"], true);
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_prints_the_signer_and_the_expected_signer() {
+        let output = sui(&["verify", "--signature", SIGNATURE, "--market-id", "0x3", "--order", ORDER]).await.unwrap();
+        assert_eq!(output, json!({"valid": true, "signer": ADDRESS, "expectedSigner": ADDRESS}));
+
+        let other_salt = ORDER.replace("1695466663327515", "1695466663327516");
+        let output = sui(&["verify", "--signature", SIGNATURE, "--market-id", "0x3", "--order", &other_salt]).await.unwrap();
+        assert_eq!(output["valid"], false);
+
+        let output = sui(&["verify", "--signature", SIGNATURE, "--hash", ORDER_HASH]).await.unwrap();
+        assert_eq!(output, json!({"valid": false, "signer": ADDRESS, "expectedSigner": ADDRESS}));
+    }
+
+    #[tokio::test]
+    async fn decode_prints_an_order_sign_order_reads_back() {
+        let output = sui(&["decode", "--order", SERIALIZED_ORDER, "--market", "ETH-PERP"]).await.unwrap();
+
+        assert_eq!(keys(&output), ["hash", "marketId", "order"]);
+        assert_eq!(output["hash"], ORDER_HASH);
+        assert_eq!(output["marketId"], format!("0x{:0>64}", "3"));
+        assert_eq!(output["order"]["market"], "ETH-PERP");
+        assert_eq!(output["order"]["quantity"], "0.01");
+        assert_eq!(output["order"]["maker"], ADDRESS);
+
+        let order = output["order"].to_string();
+        let signed = sui(&["sign-order", "--market-id", "0x3", "--order", &order]).await.unwrap();
+        assert_eq!(signed["serializedOrder"], SERIALIZED_ORDER);
+        assert_eq!(signed["hash"], ORDER_HASH);
+    }
+
+    #[tokio::test]
+    async fn decode_names_the_market_from_the_registry_or_refuses() {
+        let markets = std::env::temp_dir().join(format!("bluefin-markets-{}.json", std::process::id()));
+        fs::write(&markets, r#"{"ETH-PERP": "0x3"}"#).unwrap();
+        let output = sui(&["decode", "--order", &format!("0x{}", SERIALIZED_ORDER), "--markets", markets.to_str().unwrap()]).await;
+        fs::remove_file(&markets).unwrap();
+        assert_eq!(output.unwrap()["order"]["market"], "ETH-PERP");
+
+        assert!(sui(&["decode", "--order", SERIALIZED_ORDER]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn decode_prints_both_formats_of_a_signature() {
+        let output = sui(&["decode", "--signature", SIGNATURE]).await.unwrap();
+
+        assert_eq!(keys(&output), ["publicKey", "scheme", "signer", "sui", "wire"]);
+        assert_eq!(output["scheme"], "Ed25519");
+        assert_eq!(output["signer"], ADDRESS);
+        assert_eq!(output["wire"], SIGNATURE);
+        let sui_format = output["sui"].as_str().unwrap();
+        assert_eq!(sui(&["decode", "--signature", sui_format]).await.unwrap(), output);
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Result, SigningError};
+
+/**
+ * Fixed point amount with 18 decimals, stored as its 1e18 ("wei") representation.
+ * The range is that of the solidity uint128 type the exchange encodes amounts into
+ */
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount(u128);
+
+impl Amount {
+    pub const DECIMALS: u32 = 18;
+
+    pub const ZERO: Amount = Amount(0);
+
+    const SCALE: u128 = 10u128.pow(Amount::DECIMALS);
+
+    /**
+     * Creates an amount from its 1e18 representation
+     */
+    pub fn from_wei(wei: u128) -> Self {
+        Amount(wei)
+    }
+
+    /**
+     * Parses the 1e18 representation given as a decimal integer string
+     */
+    pub fn from_wei_str(wei: &str) -> Result<Self> {
+        if wei.is_empty() || !wei.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(SigningError::InvalidInput(format!("{} is not a decimal integer", wei)));
+        }
+        wei.parse()
+            .map(Amount)
+            .map_err(|_| SigningError::Overflow(format!("{} does not fit in uint128", wei)))
+    }
+
+    /**
+     * Returns the 1e18 representation
+     */
+    pub fn wei(&self) -> u128 {
+        self.0
+    }
+}
+
+/**
+ * Parses a human readable decimal such as "1800.5" or "0.02"
+ */
+impl FromStr for Amount {
+    type Err = SigningError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || SigningError::InvalidInput(format!("{} is not a decimal amount", value));
+        let overflow = || SigningError::Overflow(format!("{} does not fit in uint128", value));
+
+        let (integer, fraction) = match value.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (value, ""),
+        };
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+        if !integer.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        if fraction.len() > Amount::DECIMALS as usize {
+            return Err(SigningError::InvalidInput(format!(
+                "{} has more than {} decimal places",
+                value,
+                Amount::DECIMALS
+            )));
+        }
+
+        let integer: u128 = if integer.is_empty() { 0 } else { integer.parse().map_err(|_| overflow())? };
+        let fraction: u128 = format!("{:0<18}", fraction).parse().map_err(|_| invalid())?;
+
+        integer
+            .checked_mul(Amount::SCALE)
+            .and_then(|wei| wei.checked_add(fraction))
+            .map(Amount)
+            .ok_or_else(overflow)
+    }
+}
+
+/**
+ * Displays the human readable decimal, without trailing zeros
+ */
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let integer = self.0 / Amount::SCALE;
+        let fraction = self.0 % Amount::SCALE;
+        if fraction == 0 {
+            return write!(f, "{}", integer);
+        }
+        let fraction = format!("{:018}", fraction);
+        write!(f, "{}.{}", integer, fraction.trim_end_matches('0'))
+    }
+}
+
+impl fmt::Debug for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Amount({})", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wei(value: &str) -> u128 {
+        value.parse::<Amount>().unwrap().wei()
+    }
+
+    #[test]
+    fn parses_decimal_amounts() {
+        assert_eq!(wei("1800.5"), 1_800_500_000_000_000_000_000);
+        assert_eq!(wei(".5"), 500_000_000_000_000_000);
+        assert_eq!(wei("5."), 5_000_000_000_000_000_000);
+        assert_eq!(wei("0.000000000000000001"), 1);
+        assert_eq!(wei("0"), 0);
+        assert_eq!(wei("340282366920938463463"), u128::MAX / Amount::SCALE * Amount::SCALE);
+    }
+
+    #[test]
+    fn rejects_malformed_amounts() {
+        for value in ["", ".", "-1", "+1", "1e18", "1,5", " 1", "1.2.3", "0x10"] {
+            assert!(matches!(value.parse::<Amount>(), Err(SigningError::InvalidInput(_))), "{:?}", value);
+        }
+        assert!(matches!("0.0000000000000000001".parse::<Amount>(), Err(SigningError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn rejects_amounts_above_uint128() {
+        let max = Amount::from_wei(u128::MAX).to_string();
+        assert_eq!(wei(&max), u128::MAX);
+        assert!(matches!("340282366920938463464".parse::<Amount>(), Err(SigningError::Overflow(_))));
+        assert!(matches!("340282366920938463463.374607431768211456".parse::<Amount>(), Err(SigningError::Overflow(_))));
+        assert!(matches!("1000000000000000000000000000000000000000".parse::<Amount>(), Err(SigningError::Overflow(_))));
+    }
+
+    #[test]
+    fn parses_wei_strings() {
+        assert_eq!(Amount::from_wei_str("1800500000000000000000").unwrap().to_string(), "1800.5");
+        assert_eq!(Amount::from_wei_str(&u128::MAX.to_string()).unwrap().wei(), u128::MAX);
+        assert!(matches!(Amount::from_wei_str("340282366920938463463374607431768211456"), Err(SigningError::Overflow(_))));
+        for value in ["", "-1", "1.5"] {
+            assert!(matches!(Amount::from_wei_str(value), Err(SigningError::InvalidInput(_))), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn displays_without_trailing_zeros() {
+        assert_eq!(Amount::from_wei(1_800_500_000_000_000_000_000).to_string(), "1800.5");
+        assert_eq!(Amount::from_wei(5_000_000_000_000_000_000).to_string(), "5");
+        assert_eq!(Amount::from_wei(1).to_string(), "0.000000000000000001");
+        assert_eq!(Amount::ZERO.to_string(), "0");
+        assert_eq!(format!("{:?}", Amount::from_wei(20_000_000_000_000_000)), "Amount(0.02)");
+    }
+}
@@ -0,0 +1,36 @@
+//! REST API types shared by the Arbitrum and Sui deployments, which answer the same routes the same way.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/**
+ * Number of order hashes put in one cancellation request by `create_cancel_requests`.
+ * The exchange does not publish its limit, this is a conservative default
+ */
+pub const MAX_CANCEL_ORDER_HASHES: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizeResponse {
+    pub token: String,
+}
+
+/**
+ * Answer to a placed order, `hash` is the order hash computed by the exchange
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderResponse {
+    pub hash: String,
+}
+
+/**
+ * Answer to a cancellation, the exchange reports which hashes it accepted for cancelling in `data`
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrdersResponse {
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub data: Value,
+}
@@ -0,0 +1,154 @@
+use ethers::types::H160;
+use ethers_signers::LocalWallet;
+use serde::{Deserialize, Serialize};
+
+use super::order::{get_cancel_hash, sign_order, Order};
+pub use crate::api::{AuthorizeResponse, CancelOrdersResponse, OrderResponse, MAX_CANCEL_ORDER_HASHES};
+use crate::{OrderKind, OrderType, Result, Side, SigningError, TimeInForce};
+
+/**
+ * Body of the /authorize route, exchanging an onboarding signature for a JWT token
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizeRequest {
+    pub signature: String,
+    pub user_address: String,
+    pub is_term_accepted: bool,
+}
+
+impl AuthorizeRequest {
+    /**
+     * Creates the request from a signature made by `sign_onboarding_message`
+     */
+    pub fn new(onboarding_signature: &str, user_address: H160) -> Self {
+        AuthorizeRequest {
+            signature: onboarding_signature.to_string(),
+            user_address: format!("{:?}", user_address),
+            is_term_accepted: true,
+        }
+    }
+}
+
+/**
+ * Signed order as posted to the exchange, amounts in their 1e18 representation
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderJSONRequest {
+    pub symbol: String,
+    pub user_address: String,
+    pub order_type: OrderType,
+    pub price: String,
+    pub trigger_price: String,
+    pub quantity: String,
+    pub leverage: String,
+    pub side: Side,
+    pub reduce_only: bool,
+    pub salt: u128,
+    pub expiration: u128,
+    pub order_signature: String,
+    pub time_in_force: TimeInForce,
+    pub post_only: bool,
+    pub cancel_on_revert: bool,
+    pub client_id: String,
+}
+
+impl OrderJSONRequest {
+    /**
+     * Creates the request placing an order signed by `sign_order`, good till time and not post only.
+     * Change `time_in_force` or `post_only` before posting for other orders
+     */
+    pub fn new(symbol: &str, order: &Order, order_signature: &str, order_type: OrderType) -> Self {
+        OrderJSONRequest {
+            symbol: symbol.to_string(),
+            user_address: format!("{:?}", order.maker),
+            order_type,
+            price: order.price.wei().to_string(),
+            trigger_price: order.trigger_price.wei().to_string(),
+            quantity: order.quantity.wei().to_string(),
+            leverage: order.leverage.wei().to_string(),
+            side: if order.is_buy { Side::Buy } else { Side::Sell },
+            reduce_only: order.reduce_only,
+            salt: order.salt,
+            expiration: order.expiration,
+            order_signature: order_signature.to_string(),
+            time_in_force: TimeInForce::Gtt,
+            post_only: false,
+            cancel_on_revert: false,
+            client_id: "bluefin-signing".to_string(),
+        }
+    }
+
+    /**
+     * Rejects order type, time in force, price and trigger price combinations the exchange refuses
+     */
+    pub fn validate(&self) -> Result<()> {
+        OrderKind {
+            order_type: self.order_type,
+            time_in_force: self.time_in_force,
+            post_only: self.post_only,
+            has_price: self.price != "0",
+            has_trigger_price: self.trigger_price != "0",
+        }
+        .validate()
+    }
+}
+
+/**
+ * Cancellation of orders, signed as a whole with the EIP-712 cancel hash of exactly these order hashes.
+ * `parent_address` is the account the orders belong to when a sub-account cancels them, otherwise it is empty
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderCancellationJSONRequest {
+    pub symbol: String,
+    pub order_hashes: Vec<String>,
+    pub cancel_signature: String,
+    pub parent_address: String,
+}
+
+impl OrderCancellationJSONRequest {
+    pub fn new(symbol: &str, order_hashes: &[&str], cancel_signature: &str, parent_address: Option<H160>) -> Self {
+        OrderCancellationJSONRequest {
+            symbol: symbol.to_string(),
+            order_hashes: order_hashes
+                .iter()
+                .map(|order_hash| format!("0x{}", order_hash.trim_start_matches("0x")))
+                .collect(),
+            cancel_signature: cancel_signature.to_string(),
+            parent_address: parent_address
+                .map(|parent_address| format!("{:?}", parent_address))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/**
+ * Signs the cancellation of any number of order hashes, split into requests of at most `max_order_hashes`
+ * hashes each, every request carrying the signature of its own hashes
+ */
+pub async fn create_cancel_requests(
+    wallet: &LocalWallet,
+    symbol: &str,
+    order_hashes: &[&str],
+    trader_contract: &str,
+    network_id: &str,
+    parent_address: Option<H160>,
+    max_order_hashes: usize,
+) -> Result<Vec<OrderCancellationJSONRequest>> {
+    if order_hashes.is_empty() {
+        return Err(SigningError::InvalidInput("at least one order hash is required".to_string()));
+    }
+    if max_order_hashes == 0 {
+        return Err(SigningError::InvalidInput("max_order_hashes must be at least 1".to_string()));
+    }
+
+    let mut requests = Vec::new();
+    for chunk in order_hashes.chunks(max_order_hashes) {
+        let cancel_hash = get_cancel_hash(chunk, trader_contract, network_id)?;
+        let cancel_signature = sign_order(wallet, &cancel_hash).await?;
+        requests.push(OrderCancellationJSONRequest::new(symbol, chunk, &cancel_signature, parent_address));
+    }
+    Ok(requests)
+}
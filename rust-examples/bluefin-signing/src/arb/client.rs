@@ -0,0 +1,235 @@
+use ethers::types::H160;
+
+use super::api::{AuthorizeRequest, CancelOrdersResponse, OrderCancellationJSONRequest, OrderJSONRequest, OrderResponse};
+use crate::transport::{ReqwestTransport, RestClient, RetryPolicy, Transport};
+use crate::{Environment, Result};
+
+/**
+ * Client of the Firefly/Bluefin Arbitrum REST API of an environment, the counterpart of `sui::BluefinClient`.
+ * Authorize and cancels are retried by the retry policy, placing an order never is: see `post_order_with_retry`
+ */
+#[derive(Debug, Clone)]
+pub struct BluefinClient<T: Transport = ReqwestTransport> {
+    rest: RestClient<T>,
+}
+
+impl BluefinClient {
+    pub fn new(environment: Environment) -> Self {
+        BluefinClient::with_transport(environment, ReqwestTransport::default())
+    }
+}
+
+impl<T: Transport> BluefinClient<T> {
+    pub fn with_transport(environment: Environment, transport: T) -> Self {
+        BluefinClient {
+            rest: RestClient::with_transport(environment, transport),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.rest = self.rest.with_retry_policy(retry_policy);
+        self
+    }
+
+    pub fn environment(&self) -> &Environment {
+        self.rest.environment()
+    }
+
+    pub fn transport(&self) -> &T {
+        self.rest.transport()
+    }
+
+    /**
+     * POSTS the onboarding signature and returns the JWT token
+     */
+    pub async fn authorize(&self, onboarding_signature: &str, user_address: H160) -> Result<String> {
+        self.rest
+            .authorize(&AuthorizeRequest::new(onboarding_signature, user_address))
+            .await
+    }
+
+    /**
+     * Validates and POSTS the signed order once, see `RestClient::post_order`
+     */
+    pub async fn post_order(&self, order_request: &OrderJSONRequest, jwt_token: &str) -> Result<OrderResponse> {
+        order_request.validate()?;
+        self.rest.post_order(order_request, jwt_token).await
+    }
+
+    /**
+     * Validates and POSTS the signed order with retries, see `RestClient::post_order_with_retry`
+     */
+    pub async fn post_order_with_retry(&self, order_request: &OrderJSONRequest, jwt_token: &str) -> Result<OrderResponse> {
+        order_request.validate()?;
+        self.rest.post_order_with_retry(order_request, jwt_token).await
+    }
+
+    /**
+     * Sends the Cancellation Order
+     */
+    pub async fn cancel_orders(
+        &self,
+        order_cancel: &OrderCancellationJSONRequest,
+        jwt_token: &str,
+    ) -> Result<CancelOrdersResponse> {
+        self.rest.cancel_orders(order_cancel, jwt_token).await
+    }
+
+    /**
+     * Sends the requests made by `create_cancel_requests`, see `RestClient::cancel_orders_in_chunks`
+     */
+    pub async fn cancel_orders_in_chunks(
+        &self,
+        order_cancels: &[OrderCancellationJSONRequest],
+        jwt_token: &str,
+    ) -> Result<Vec<CancelOrdersResponse>> {
+        self.rest.cancel_orders_in_chunks(order_cancels, jwt_token).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::arb::Order;
+    use crate::transport::{HttpMethod, HttpRequest, HttpResponse, MockTransport};
+    use crate::{OrderType, SigningError};
+
+    const API_URL: &str = "https://dapi-testnet.firefly.exchange";
+
+    const MAKER: &str = "0x6f03f28bc1ebb7c9b45614bf2483e70f008a6d3d";
+
+    /**
+     * Client whose exchange answers with the given responses in turn, repeating the last one
+     */
+    fn client(responses: Vec<HttpResponse>) -> BluefinClient<MockTransport> {
+        let sent = AtomicUsize::new(0);
+        let transport = MockTransport::new(move |_| {
+            let index = sent.fetch_add(1, Ordering::SeqCst).min(responses.len() - 1);
+            responses[index].clone()
+        });
+        BluefinClient::with_transport(Environment::arb_testnet(), transport).with_retry_policy(RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        })
+    }
+
+    fn header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+        request
+            .headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn body(request: &HttpRequest) -> Value {
+        serde_json::from_str(request.body.as_deref().unwrap()).unwrap()
+    }
+
+    fn order_request() -> OrderJSONRequest {
+        let order = Order {
+            is_buy: true,
+            reduce_only: true,
+            quantity: "6".parse().unwrap(),
+            price: "1800".parse().unwrap(),
+            trigger_price: "0".parse().unwrap(),
+            leverage: "0.02".parse().unwrap(),
+            expiration: 1690995498,
+            salt: 1231231231,
+            maker: MAKER.parse().unwrap(),
+        };
+        OrderJSONRequest::new("ETH-PERP", &order, "0xsignature", OrderType::Limit)
+    }
+
+    fn order_cancel() -> OrderCancellationJSONRequest {
+        OrderCancellationJSONRequest::new("ETH-PERP", &["400fbdbc"], "0xsignature", None)
+    }
+
+    #[tokio::test]
+    async fn authorize_posts_the_onboarding_signature() {
+        let client = client(vec![HttpResponse::new(200, r#"{"token": "jwt"}"#)]);
+
+        assert_eq!(client.authorize("0xonboarding", MAKER.parse().unwrap()).await.unwrap(), "jwt");
+
+        let requests = client.transport().requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, HttpMethod::Post);
+        assert_eq!(requests[0].url, format!("{}/authorize", API_URL));
+        assert_eq!(header(&requests[0], "Authorization"), None);
+        assert_eq!(
+            body(&requests[0]),
+            json!({"signature": "0xonboarding", "userAddress": MAKER, "isTermAccepted": true})
+        );
+    }
+
+    #[tokio::test]
+    async fn post_order_sends_the_order_with_the_token() {
+        let client = client(vec![HttpResponse::new(200, r#"{"hash": "0x400fbdbc"}"#)]);
+
+        assert_eq!(client.post_order(&order_request(), "jwt").await.unwrap().hash, "0x400fbdbc");
+
+        let requests = client.transport().requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, HttpMethod::Post);
+        assert_eq!(requests[0].url, format!("{}/orders", API_URL));
+        assert_eq!(header(&requests[0], "Authorization"), Some("Bearer jwt"));
+        let body = body(&requests[0]);
+        assert_eq!(body["symbol"], "ETH-PERP");
+        assert_eq!(body["userAddress"], MAKER);
+        assert_eq!(body["side"], "BUY");
+        assert_eq!(body["quantity"], "6000000000000000000");
+        assert_eq!(body["orderSignature"], "0xsignature");
+    }
+
+    #[tokio::test]
+    async fn post_order_rejects_invalid_orders_without_sending() {
+        let client = client(vec![HttpResponse::new(200, r#"{"hash": "0x400fbdbc"}"#)]);
+        let mut order_request = order_request();
+        order_request.order_type = OrderType::StopLimit;
+
+        assert!(matches!(
+            client.post_order(&order_request, "jwt").await,
+            Err(SigningError::InvalidInput(_))
+        ));
+        assert!(client.transport().requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancel_orders_deletes_the_hashes_with_the_token() {
+        let client = client(vec![HttpResponse::new(200, r#"{"message": "ok", "data": {}}"#)]);
+
+        assert_eq!(client.cancel_orders(&order_cancel(), "jwt").await.unwrap().message.as_deref(), Some("ok"));
+
+        let requests = client.transport().requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, HttpMethod::Delete);
+        assert_eq!(requests[0].url, format!("{}/orders/hash", API_URL));
+        assert_eq!(header(&requests[0], "Authorization"), Some("Bearer jwt"));
+        assert_eq!(
+            body(&requests[0]),
+            json!({
+                "symbol": "ETH-PERP",
+                "orderHashes": ["0x400fbdbc"],
+                "cancelSignature": "0xsignature",
+                "parentAddress": "",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn cancel_orders_in_chunks_stops_at_the_first_failure() {
+        let client = client(vec![HttpResponse::new(200, r#"{"message": "ok"}"#), HttpResponse::new(400, "bad hash")])
+            .with_retry_policy(RetryPolicy::none());
+
+        let order_cancels = vec![order_cancel(), order_cancel(), order_cancel()];
+        assert!(matches!(
+            client.cancel_orders_in_chunks(&order_cancels, "jwt").await,
+            Err(SigningError::Exchange { status: 400, .. })
+        ));
+        assert_eq!(client.transport().requests().len(), 2);
+    }
+}
@@ -0,0 +1,474 @@
+use ethers::types::{H160, U256};
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Result, SigningError};
+
+pub const EIP712_DOMAIN_TYPE: &str = "EIP712Domain";
+
+/**
+ * A member of an EIP-712 struct type, e.g. `bytes32[] orderHashes`
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypedDataField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+}
+
+/**
+ * Struct type definitions keyed by type name, including the EIP712Domain type
+ */
+pub type TypedDataTypes = BTreeMap<String, Vec<TypedDataField>>;
+
+/**
+ * EIP-712 typed data, laid out like the `eth_signTypedData_v4` JSON payload
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    pub types: TypedDataTypes,
+    pub primary_type: String,
+    pub domain: Value,
+    pub message: Value,
+}
+
+/**
+ * Parses one or more concatenated type strings, e.g.
+ * `CancelLimitOrder(string action,bytes32[] orderHashes)`, into struct type definitions
+ */
+pub fn parse_types(type_string: &str) -> Result<TypedDataTypes> {
+    let mut types = TypedDataTypes::new();
+    let mut rest = type_string.trim();
+
+    while !rest.is_empty() {
+        let invalid = || SigningError::InvalidInput(format!("malformed EIP-712 type string: {}", type_string));
+        let open = rest.find('(').ok_or_else(invalid)?;
+        let close = rest.find(')').ok_or_else(invalid)?;
+        if close < open {
+            return Err(invalid());
+        }
+
+        let name = rest[..open].trim();
+        if name.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut fields = Vec::new();
+        for member in rest[open + 1..close].split(',').filter(|m| !m.trim().is_empty()) {
+            let mut parts = member.split_whitespace();
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(field_type), Some(field_name), None) => fields.push(TypedDataField {
+                    name: field_name.to_string(),
+                    field_type: field_type.to_string(),
+                }),
+                _ => return Err(invalid()),
+            }
+        }
+
+        types.insert(name.to_string(), fields);
+        rest = rest[close + 1..].trim_start();
+    }
+
+    Ok(types)
+}
+
+/**
+ * Strips any array suffixes from a type, `bytes32[][2]` becomes `bytes32`
+ */
+fn get_base_type(field_type: &str) -> &str {
+    match field_type.find('[') {
+        Some(index) => &field_type[..index],
+        None => field_type,
+    }
+}
+
+/**
+ * Reads the bit size of an intN/uintN type, defaulting to 256
+ */
+fn get_int_bits(field_type: &str, prefix: &str) -> Result<usize> {
+    let size = &field_type[prefix.len()..];
+    if size.is_empty() {
+        return Ok(256);
+    }
+    match size.parse::<usize>() {
+        Ok(bits) if bits > 0 && bits <= 256 && bits % 8 == 0 => Ok(bits),
+        _ => Err(SigningError::InvalidInput(format!("unknown EIP-712 type {}", field_type))),
+    }
+}
+
+/**
+ * Parses an integer given as a JSON number, decimal string or 0x prefixed hex string.
+ * Returns the magnitude and whether the value is negative
+ */
+fn parse_integer(value: &Value, field: &str) -> Result<(U256, bool)> {
+    let invalid = || SigningError::InvalidInput(format!("{} is not an integer: {}", field, value));
+    match value {
+        Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+            (Some(n), _) => Ok((U256::from(n), false)),
+            (None, Some(n)) => Ok((U256::from(n.unsigned_abs()), true)),
+            _ => Err(invalid()),
+        },
+        Value::String(text) => {
+            let (negative, digits) = match text.strip_prefix('-') {
+                Some(digits) => (true, digits),
+                None => (false, text.as_str()),
+            };
+            let magnitude = match digits.strip_prefix("0x") {
+                Some(hex_digits) => U256::from_str_radix(hex_digits, 16).ok(),
+                None => U256::from_dec_str(digits).ok(),
+            }
+            .ok_or_else(invalid)?;
+            Ok((magnitude, negative && !magnitude.is_zero()))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/**
+ * Decodes a 0x prefixed hex string value
+ */
+fn parse_hex_value(value: &Value, field: &str) -> Result<Vec<u8>> {
+    let text = value
+        .as_str()
+        .ok_or_else(|| SigningError::InvalidInput(format!("{} must be a hex string: {}", field, value)))?;
+    hex::decode(text.trim_start_matches("0x"))
+        .map_err(|e| SigningError::InvalidInput(format!("{} is not valid hex: {}", field, e)))
+}
+
+/**
+ * Encodes an atomic value into its 32 byte EIP-712 word
+ */
+fn encode_atomic(field_type: &str, value: &Value, field: &str) -> Result<[u8; 32]> {
+    let mut word = [0u8; 32];
+
+    match field_type {
+        "string" => {
+            let text = value
+                .as_str()
+                .ok_or_else(|| SigningError::InvalidInput(format!("{} must be a string: {}", field, value)))?;
+            word = keccak256(text.as_bytes());
+        }
+        "bytes" => {
+            word = keccak256(parse_hex_value(value, field)?);
+        }
+        "bool" => {
+            let flag = match value {
+                Value::Bool(flag) => *flag,
+                Value::String(text) if text == "true" || text == "false" => text == "true",
+                _ => return Err(SigningError::InvalidInput(format!("{} must be a bool: {}", field, value))),
+            };
+            word[31] = flag as u8;
+        }
+        "address" => {
+            let text = value
+                .as_str()
+                .ok_or_else(|| SigningError::InvalidInput(format!("{} must be an address: {}", field, value)))?;
+            let address: H160 = text
+                .parse()
+                .map_err(|e| SigningError::InvalidInput(format!("{} is not an address: {}", field, e)))?;
+            word[12..].copy_from_slice(address.as_bytes());
+        }
+        _ if field_type.starts_with("bytes") => {
+            let size: usize = field_type["bytes".len()..]
+                .parse()
+                .map_err(|_| SigningError::InvalidInput(format!("unknown EIP-712 type {}", field_type)))?;
+            let bytes = parse_hex_value(value, field)?;
+            if size == 0 || size > 32 {
+                return Err(SigningError::InvalidInput(format!("unknown EIP-712 type {}", field_type)));
+            }
+            if bytes.len() > size {
+                return Err(SigningError::Overflow(format!(
+                    "{} must be {} bytes, got {}",
+                    field,
+                    size,
+                    bytes.len()
+                )));
+            }
+            if bytes.len() != size {
+                return Err(SigningError::InvalidInput(format!(
+                    "{} must be {} bytes, got {}",
+                    field,
+                    size,
+                    bytes.len()
+                )));
+            }
+            word[..size].copy_from_slice(&bytes);
+        }
+        _ if field_type.starts_with("uint") => {
+            let bits = get_int_bits(field_type, "uint")?;
+            let (number, negative) = parse_integer(value, field)?;
+            if negative || (bits < 256 && number >= U256::one() << bits) {
+                return Err(SigningError::Overflow(format!("{} does not fit in {}", field, field_type)));
+            }
+            number.to_big_endian(&mut word);
+        }
+        _ if field_type.starts_with("int") => {
+            let bits = get_int_bits(field_type, "int")?;
+            let (number, negative) = parse_integer(value, field)?;
+            let limit = U256::one() << (bits - 1);
+            if (negative && number > limit) || (!negative && number >= limit) {
+                return Err(SigningError::Overflow(format!("{} does not fit in {}", field, field_type)));
+            }
+            let encoded = if negative { (!number).overflowing_add(U256::one()).0 } else { number };
+            encoded.to_big_endian(&mut word);
+        }
+        _ => return Err(SigningError::InvalidInput(format!("unknown EIP-712 type {}", field_type))),
+    }
+
+    Ok(word)
+}
+
+impl TypedData {
+    pub fn new(types: TypedDataTypes, primary_type: &str, domain: Value, message: Value) -> Self {
+        TypedData {
+            types,
+            primary_type: primary_type.to_string(),
+            domain,
+            message,
+        }
+    }
+
+    fn get_fields(&self, type_name: &str) -> Result<&Vec<TypedDataField>> {
+        self.types
+            .get(type_name)
+            .ok_or_else(|| SigningError::InvalidInput(format!("EIP-712 type {} is not defined", type_name)))
+    }
+
+    /**
+     * Collects the struct types referenced by a type, directly or through nested structs
+     */
+    fn find_dependencies(&self, type_name: &str, found: &mut BTreeSet<String>) -> Result<()> {
+        for field in self.get_fields(type_name)? {
+            let base_type = get_base_type(&field.field_type);
+            if self.types.contains_key(base_type) && found.insert(base_type.to_string()) {
+                self.find_dependencies(base_type, found)?;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Returns the encodeType string: the type itself followed by the referenced struct types in alphabetical order
+     */
+    pub fn encode_type(&self, type_name: &str) -> Result<String> {
+        let mut dependencies = BTreeSet::new();
+        self.find_dependencies(type_name, &mut dependencies)?;
+        dependencies.remove(type_name);
+
+        let mut encoded = String::new();
+        for name in std::iter::once(type_name).chain(dependencies.iter().map(String::as_str)) {
+            let members: Vec<String> = self
+                .get_fields(name)?
+                .iter()
+                .map(|field| format!("{} {}", field.field_type, field.name))
+                .collect();
+            encoded += &format!("{}({})", name, members.join(","));
+        }
+        Ok(encoded)
+    }
+
+    pub fn type_hash(&self, type_name: &str) -> Result<[u8; 32]> {
+        Ok(keccak256(self.encode_type(type_name)?.as_bytes()))
+    }
+
+    /**
+     * Encodes a single member value, hashing structs, dynamic values and arrays as EIP-712 requires
+     */
+    fn encode_field(&self, field_type: &str, value: &Value, field: &str) -> Result<[u8; 32]> {
+        if let Some(element_type) = field_type.strip_suffix(']') {
+            let open = element_type
+                .rfind('[')
+                .ok_or_else(|| SigningError::InvalidInput(format!("unknown EIP-712 type {}", field_type)))?;
+            let length = &element_type[open + 1..];
+            let element_type = &element_type[..open];
+
+            let items = value
+                .as_array()
+                .ok_or_else(|| SigningError::InvalidInput(format!("{} must be an array: {}", field, value)))?;
+            if !length.is_empty() && length.parse::<usize>().ok() != Some(items.len()) {
+                return Err(SigningError::InvalidInput(format!(
+                    "{} must have {} elements, got {}",
+                    field,
+                    length,
+                    items.len()
+                )));
+            }
+
+            let mut encoded = Vec::with_capacity(items.len() * 32);
+            for item in items {
+                encoded.extend_from_slice(&self.encode_field(element_type, item, field)?);
+            }
+            return Ok(keccak256(encoded));
+        }
+
+        if self.types.contains_key(field_type) {
+            return self.hash_struct(field_type, value);
+        }
+
+        encode_atomic(field_type, value, field)
+    }
+
+    /**
+     * Returns encodeData: the type hash followed by the encoding of every member
+     */
+    pub fn encode_data(&self, type_name: &str, value: &Value) -> Result<Vec<u8>> {
+        let fields = self.get_fields(type_name)?;
+        let mut encoded = Vec::with_capacity((fields.len() + 1) * 32);
+        encoded.extend_from_slice(&self.type_hash(type_name)?);
+
+        for field in fields {
+            let member = value.get(&field.name).ok_or_else(|| {
+                SigningError::InvalidInput(format!("{} is missing member {}", type_name, field.name))
+            })?;
+            encoded.extend_from_slice(&self.encode_field(&field.field_type, member, &field.name)?);
+        }
+        Ok(encoded)
+    }
+
+    pub fn hash_struct(&self, type_name: &str, value: &Value) -> Result<[u8; 32]> {
+        Ok(keccak256(self.encode_data(type_name, value)?))
+    }
+
+    pub fn domain_separator(&self) -> Result<[u8; 32]> {
+        self.hash_struct(EIP712_DOMAIN_TYPE, &self.domain)
+    }
+
+    pub fn message_hash(&self) -> Result<[u8; 32]> {
+        self.hash_struct(&self.primary_type, &self.message)
+    }
+
+    /**
+     * Returns the final digest keccak256("\x19\x01" || domainSeparator || hashStruct(message))
+     */
+    pub fn digest(&self) -> Result<[u8; 32]> {
+        let mut data = Vec::with_capacity(66);
+        data.extend_from_slice(&[0x19, 0x01]);
+        data.extend_from_slice(&self.domain_separator()?);
+        data.extend_from_slice(&self.message_hash()?);
+        Ok(keccak256(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /**
+     * The example of the EIP-712 specification, as the eth_signTypedData_v4 JSON payload
+     */
+    fn mail() -> TypedData {
+        serde_json::from_value(json!({
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "Person": [
+                    {"name": "name", "type": "string"},
+                    {"name": "wallet", "type": "address"}
+                ],
+                "Mail": [
+                    {"name": "from", "type": "Person"},
+                    {"name": "to", "type": "Person"},
+                    {"name": "contents", "type": "string"}
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+                "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+                "contents": "Hello, Bob!"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn encodes_the_mail_example() {
+        let typed_data = mail();
+        assert_eq!(
+            typed_data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            hex::encode(typed_data.type_hash("Mail").unwrap()),
+            "a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2"
+        );
+        assert_eq!(
+            hex::encode(typed_data.message_hash().unwrap()),
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+        );
+        assert_eq!(
+            hex::encode(typed_data.domain_separator().unwrap()),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+        assert_eq!(
+            hex::encode(typed_data.digest().unwrap()),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+    }
+
+    #[test]
+    fn parses_type_strings_like_the_json_types() {
+        let types = parse_types("Mail(Person from,Person to,string contents)Person(string name,address wallet)").unwrap();
+        assert_eq!(types["Mail"], mail().types["Mail"]);
+        assert_eq!(types["Person"], mail().types["Person"]);
+        assert!(parse_types("Mail(Person from").is_err());
+        assert!(parse_types("Mail(Person)").is_err());
+    }
+
+    #[test]
+    fn hashes_dynamic_arrays_by_concatenating_their_elements() {
+        let order_hashes = [[0x11u8; 32], [0x22u8; 32]];
+        let typed_data = TypedData::new(
+            parse_types("CancelLimitOrder(string action,bytes32[] orderHashes)").unwrap(),
+            "CancelLimitOrder",
+            Value::Null,
+            json!({
+                "action": "Cancel Orders",
+                "orderHashes": order_hashes.iter().map(|hash| format!("0x{}", hex::encode(hash))).collect::<Vec<_>>(),
+            }),
+        );
+
+        let expected = keccak256(
+            [
+                keccak256("CancelLimitOrder(string action,bytes32[] orderHashes)".as_bytes()),
+                keccak256("Cancel Orders".as_bytes()),
+                keccak256(order_hashes.concat()),
+            ]
+            .concat(),
+        );
+        assert_eq!(typed_data.message_hash().unwrap(), expected);
+    }
+
+    #[test]
+    fn rejects_values_that_do_not_fit_their_type() {
+        let typed_data = TypedData::new(
+            parse_types("Test(uint8 small,bytes8 flags)").unwrap(),
+            "Test",
+            Value::Null,
+            json!({"small": 256, "flags": "0x00"}),
+        );
+        assert!(matches!(typed_data.message_hash(), Err(SigningError::Overflow(_))));
+
+        let typed_data = TypedData::new(
+            parse_types("Test(uint8 small,bytes8 flags)").unwrap(),
+            "Test",
+            Value::Null,
+            json!({"small": 255, "flags": "0x00"}),
+        );
+        assert!(matches!(typed_data.message_hash(), Err(SigningError::InvalidInput(_))));
+    }
+}
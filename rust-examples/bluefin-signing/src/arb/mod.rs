@@ -0,0 +1,14 @@
+//! Bluefin on Arbitrum: EIP-712 typed data and order hashing, order/cancel signing and verification, onboarding and the REST API.
+
+pub mod api;
+pub mod client;
+pub mod eip712;
+pub mod onboarding;
+pub mod order;
+pub mod signature;
+
+pub use client::BluefinClient;
+pub use eip712::TypedData;
+pub use onboarding::{get_onboarding_hash, sign_onboarding_message};
+pub use order::{decode_flags, encode_flags, get_cancel_hash, get_hash, sign_order, Order, OrderFlags};
+pub use signature::{recover_signer, verify_cancel_signature, verify_order_signature};
@@ -0,0 +1,24 @@
+use ethers::utils::keccak256;
+use ethers_signers::{LocalWallet, Signer};
+
+use crate::{Result, SigningError};
+
+/**
+ * Returns the keccak hash of the onboarding message.
+ * The message is the onboarding url of the environment, `Environment::onboarding_url`
+ */
+pub fn get_onboarding_hash(msg: &str) -> String {
+    hex::encode(keccak256(msg.as_bytes()))
+}
+
+/**
+ * Signs the hash of the onboarding message, the returned signature is sent to the /authorize route
+ */
+pub async fn sign_onboarding_message(wallet: &LocalWallet, msg: &str) -> Result<String> {
+    let hash = keccak256(msg.as_bytes());
+    let signature = wallet
+        .sign_message(hash)
+        .await
+        .map_err(|e| SigningError::Key(e.to_string()))?;
+    Ok(format!("0x{}", signature))
+}
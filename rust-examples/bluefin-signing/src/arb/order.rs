@@ -0,0 +1,333 @@
+use ethers::types::H160;
+use ethers_signers::{LocalWallet, Signer};
+use serde_json::{json, Value};
+
+use super::eip712::{parse_types, TypedData, TypedDataTypes};
+use super::signature::SignatureType;
+use crate::{Amount, Result, SigningError};
+
+const EIP712_ORDER_STRUCT_STRING: &str = "Order(bytes8 flags,uint128 quantity,uint128 price,uint128 triggerPrice,uint128 leverage,address maker,uint128 expiration)";
+
+const EIP712_CANCEL_ORDER_STRUCT_STRING: &str = "CancelLimitOrder(string action,bytes32[] orderHashes)";
+
+const EIP712_DOMAIN_STRING: &str = "EIP712Domain(string name,string version,uint128 chainId,address verifyingContract)";
+
+const EIP712_DOMAIN_NAME: &str = "IsolatedTrader";
+
+const EIP712_DOMAIN_VERSION: &str = "1.0";
+
+const EIP712_CANCEL_ACTION: &str = "Cancel Orders";
+
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub is_buy: bool,
+    pub reduce_only: bool,
+    pub quantity: Amount,
+    pub price: Amount,
+    pub trigger_price: Amount,
+    pub leverage: Amount,
+    pub expiration: u128,
+    pub salt: u128,
+    pub maker: H160,
+}
+
+/**
+ * Decodes a hex string, with or without 0x prefix
+ */
+pub(crate) fn decode_hex(value: &str, field: &str) -> Result<Vec<u8>> {
+    hex::decode(value.trim_start_matches("0x"))
+        .map_err(|e| SigningError::InvalidInput(format!("{} is not valid hex: {}", field, e)))
+}
+
+/**
+ * Largest salt that fits in the flags, whose lowest 4 bits hold the boolean flags
+ */
+pub const MAX_SALT: u128 = (1 << 60) - 1;
+
+const IS_BUY_FLAG: u8 = 1;
+
+const REDUCE_ONLY_FLAG: u8 = 2;
+
+/**
+ * Contents of the bytes8 flags field of an order: the salt followed by a 4 bit boolean flag nibble
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderFlags {
+    pub is_buy: bool,
+    pub reduce_only: bool,
+    pub salt: u128,
+}
+
+/**
+ * Packs the flags into their bytes8 representation, failing if the salt does not fit in 60 bits
+ */
+pub fn encode_flags(flags: &OrderFlags) -> Result<[u8; 8]> {
+    if flags.salt > MAX_SALT {
+        return Err(SigningError::Overflow(format!(
+            "salt {} does not fit in the 60 bits of the bytes8 flags",
+            flags.salt
+        )));
+    }
+
+    let mut boolean_flag = 0;
+
+    if flags.is_buy {
+        boolean_flag |= IS_BUY_FLAG;
+    };
+
+    if flags.reduce_only {
+        boolean_flag |= REDUCE_ONLY_FLAG;
+    };
+
+    let packed = ((flags.salt as u64) << 4) | boolean_flag as u64;
+    Ok(packed.to_be_bytes())
+}
+
+/**
+ * Unpacks the bytes8 flags produced by `encode_flags`
+ */
+pub fn decode_flags(flags: [u8; 8]) -> Result<OrderFlags> {
+    let packed = u64::from_be_bytes(flags);
+    let boolean_flag = (packed & 0xF) as u8;
+    if boolean_flag & !(IS_BUY_FLAG | REDUCE_ONLY_FLAG) != 0 {
+        return Err(SigningError::InvalidInput(format!("unknown order flags {:#x}", boolean_flag)));
+    }
+    Ok(OrderFlags {
+        is_buy: boolean_flag & IS_BUY_FLAG != 0,
+        reduce_only: boolean_flag & REDUCE_ONLY_FLAG != 0,
+        salt: (packed >> 4) as u128,
+    })
+}
+
+/**
+ * Encodes the flags of an order
+ */
+pub fn get_order_flags(order: &Order) -> Result<[u8; 8]> {
+    encode_flags(&OrderFlags {
+        is_buy: order.is_buy,
+        reduce_only: order.reduce_only,
+        salt: order.salt,
+    })
+}
+
+/**
+ * Returns the struct type definitions of a Bluefin message, together with the domain type
+ */
+fn get_types(struct_string: &str) -> Result<TypedDataTypes> {
+    parse_types(&format!("{}{}", EIP712_DOMAIN_STRING, struct_string))
+}
+
+/**
+ * Returns the EIP-712 domain of a trader contract
+ */
+fn get_domain(trader_contract: &str, network_id: &str) -> Value {
+    json!({
+        "name": EIP712_DOMAIN_NAME,
+        "version": EIP712_DOMAIN_VERSION,
+        "chainId": network_id,
+        "verifyingContract": trader_contract,
+    })
+}
+
+fn get_order_message(order: &Order) -> Result<Value> {
+    Ok(json!({
+        "flags": format!("0x{}", hex::encode(get_order_flags(order)?)),
+        "quantity": order.quantity.wei().to_string(),
+        "price": order.price.wei().to_string(),
+        "triggerPrice": order.trigger_price.wei().to_string(),
+        "leverage": order.leverage.wei().to_string(),
+        "maker": format!("{:?}", order.maker),
+        "expiration": order.expiration.to_string(),
+    }))
+}
+
+fn get_cancel_message<S: AsRef<str>>(order_hashes: &[S]) -> Result<Value> {
+    if order_hashes.is_empty() {
+        return Err(SigningError::InvalidInput("at least one order hash is required".to_string()));
+    }
+    let order_hashes: Vec<String> = order_hashes
+        .iter()
+        .map(|order_hash| format!("0x{}", order_hash.as_ref().trim_start_matches("0x")))
+        .collect();
+
+    Ok(json!({
+        "action": EIP712_CANCEL_ACTION,
+        "orderHashes": order_hashes,
+    }))
+}
+
+/**
+ * Returns the order as eth_signTypedData_v4 typed data
+ */
+pub fn get_order_typed_data(order: &Order, trader_contract: &str, network_id: &str) -> Result<TypedData> {
+    Ok(TypedData::new(
+        get_types(EIP712_ORDER_STRUCT_STRING)?,
+        "Order",
+        get_domain(trader_contract, network_id),
+        get_order_message(order)?,
+    ))
+}
+
+/**
+ * Returns the cancellation of one or more order hashes as eth_signTypedData_v4 typed data
+ */
+pub fn get_cancel_typed_data<S: AsRef<str>>(
+    order_hashes: &[S],
+    trader_contract: &str,
+    network_id: &str,
+) -> Result<TypedData> {
+    Ok(TypedData::new(
+        get_types(EIP712_CANCEL_ORDER_STRUCT_STRING)?,
+        "CancelLimitOrder",
+        get_domain(trader_contract, network_id),
+        get_cancel_message(order_hashes)?,
+    ))
+}
+
+/**
+ * Returns the EIP-712 hashStruct of the order
+ */
+pub fn get_order_data_hash(order: &Order) -> Result<String> {
+    let typed_data = TypedData::new(
+        get_types(EIP712_ORDER_STRUCT_STRING)?,
+        "Order",
+        Value::Null,
+        get_order_message(order)?,
+    );
+    Ok(hex::encode(typed_data.message_hash()?))
+}
+
+/**
+ * Given order hashes, returns the EIP-712 hashStruct of their cancellation
+ */
+pub fn get_order_cancel_hash<S: AsRef<str>>(order_hashes: &[S]) -> Result<String> {
+    let typed_data = TypedData::new(
+        get_types(EIP712_CANCEL_ORDER_STRUCT_STRING)?,
+        "CancelLimitOrder",
+        Value::Null,
+        get_cancel_message(order_hashes)?,
+    );
+    Ok(hex::encode(typed_data.message_hash()?))
+}
+
+/**
+ * Given an order, trader contract address and network id,
+ * returns EIP 712 hash of the order
+ */
+pub fn get_hash(order: &Order, trader_contract: &str, network_id: &str) -> Result<String> {
+    let typed_data = get_order_typed_data(order, trader_contract, network_id)?;
+    Ok(hex::encode(typed_data.digest()?))
+}
+
+/**
+ * Given order hashes, trader contract address and network id,
+ * returns EIP 712 hash cancelling all of the orders with one signature
+ */
+pub fn get_cancel_hash<S: AsRef<str>>(order_hashes: &[S], trader_contract: &str, network_id: &str) -> Result<String> {
+    let typed_data = get_cancel_typed_data(order_hashes, trader_contract, network_id)?;
+    Ok(hex::encode(typed_data.digest()?))
+}
+
+/**
+ * Signs an EIP 712 order or cancel hash, appending the signature type byte expected by the exchange
+ */
+pub async fn sign_order(wallet: &LocalWallet, eip712_order_hash: &str) -> Result<String> {
+    let hash = decode_hex(eip712_order_hash, "EIP 712 hash")?;
+    let signature = wallet
+        .sign_message(hash.as_slice())
+        .await
+        .map_err(|e| SigningError::Key(e.to_string()))?;
+    Ok(format!("0x{}{:02x}", signature, SignatureType::Decimal as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRADER_CONTRACT: &str = "0x934Dd6503795ef6EE6a36e3b3f1d7Be6c7096955";
+
+    const CHAIN_ID: &str = "421613";
+
+    /**
+     * The order of the arb-order-signing example
+     */
+    fn example_order() -> Order {
+        Order {
+            is_buy: true,
+            reduce_only: true,
+            quantity: "6".parse().unwrap(),
+            price: "1800".parse().unwrap(),
+            trigger_price: Amount::ZERO,
+            leverage: "0.02".parse().unwrap(),
+            expiration: 1690995498,
+            salt: 1231231231,
+            maker: "0x6f03f28bc1ebb7c9b45614bf2483e70f008a6d3d".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn order_hash_matches_the_baseline() {
+        assert_eq!(
+            get_hash(&example_order(), TRADER_CONTRACT, CHAIN_ID).unwrap(),
+            "400fbdbc69662f7bf23c57799ea6b5ff6c6c7e2742a46eccbf83b3366595bf43"
+        );
+    }
+
+    #[test]
+    fn cancel_hash_matches_the_baseline() {
+        let order_hash = "0x400fbdbc69662f7bf23c57799ea6b5ff6c6c7e2742a46eccbf83b3366595bf43";
+        assert_eq!(
+            get_cancel_hash(&[order_hash], TRADER_CONTRACT, CHAIN_ID).unwrap(),
+            "9d0ddff50c467ca2d84458369e8cbfcbfa1121432eb22245ed432aaa91738f79"
+        );
+        // the 0x prefix of the order hash is optional
+        assert_eq!(
+            get_cancel_hash(&[order_hash.trim_start_matches("0x")], TRADER_CONTRACT, CHAIN_ID).unwrap(),
+            "9d0ddff50c467ca2d84458369e8cbfcbfa1121432eb22245ed432aaa91738f79"
+        );
+        assert!(get_cancel_hash::<&str>(&[], TRADER_CONTRACT, CHAIN_ID).is_err());
+    }
+
+    #[test]
+    fn typed_data_round_trips_through_eth_sign_typed_data_v4_json() {
+        let typed_data = get_order_typed_data(&example_order(), TRADER_CONTRACT, CHAIN_ID).unwrap();
+        let json = serde_json::to_value(&typed_data).unwrap();
+        assert_eq!(json["primaryType"], "Order");
+        assert_eq!(json["types"]["EIP712Domain"][2], json!({"name": "chainId", "type": "uint128"}));
+
+        let parsed: TypedData = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            hex::encode(parsed.digest().unwrap()),
+            "400fbdbc69662f7bf23c57799ea6b5ff6c6c7e2742a46eccbf83b3366595bf43"
+        );
+    }
+
+    #[test]
+    fn flags_round_trip_up_to_the_largest_salt() {
+        for salt in [0, 1, 1231231231, MAX_SALT] {
+            for (is_buy, reduce_only) in [(false, false), (true, false), (false, true), (true, true)] {
+                let flags = OrderFlags { is_buy, reduce_only, salt };
+                assert_eq!(decode_flags(encode_flags(&flags).unwrap()).unwrap(), flags);
+            }
+        }
+        let flags = OrderFlags { is_buy: true, reduce_only: true, salt: MAX_SALT };
+        assert_eq!(encode_flags(&flags).unwrap(), [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xf3]);
+        let flags = OrderFlags { is_buy: true, reduce_only: false, salt: 1 };
+        assert_eq!(encode_flags(&flags).unwrap(), [0, 0, 0, 0, 0, 0, 0, 0x11]);
+    }
+
+    #[test]
+    fn rejects_salts_above_60_bits() {
+        for salt in [MAX_SALT + 1, u64::MAX as u128, u128::MAX] {
+            let flags = OrderFlags { is_buy: false, reduce_only: false, salt };
+            assert!(matches!(encode_flags(&flags), Err(SigningError::Overflow(_))), "{}", salt);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_flag_bits() {
+        for nibble in [0x4, 0x8, 0xc, 0xf] {
+            assert!(matches!(decode_flags([0, 0, 0, 0, 0, 0, 0, nibble]), Err(SigningError::InvalidInput(_))), "{:#x}", nibble);
+        }
+    }
+}
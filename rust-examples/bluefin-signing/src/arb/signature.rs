@@ -0,0 +1,218 @@
+use ethers::types::{Signature, H160, H256};
+use ethers::utils::keccak256;
+
+use super::order::{decode_hex, get_cancel_hash, get_hash, Order};
+use crate::{Result, SigningError};
+
+/**
+ * Trailing byte of an exchange signature, telling which prefix was applied to the hash before signing
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureType {
+    /// the hash was signed as is
+    NoPrepend = 0,
+    /// "\x19Ethereum Signed Message:\n32" was prepended, this is what `sign_order` produces
+    Decimal = 1,
+    /// "\x19Ethereum Signed Message:\n\x20" was prepended
+    Hexadecimal = 2,
+}
+
+impl TryFrom<u8> for SignatureType {
+    type Error = SigningError;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(SignatureType::NoPrepend),
+            1 => Ok(SignatureType::Decimal),
+            2 => Ok(SignatureType::Hexadecimal),
+            _ => Err(SigningError::InvalidInput(format!("unknown signature type {}", value))),
+        }
+    }
+}
+
+impl SignatureType {
+    /**
+     * Returns the digest that was actually signed for a hash
+     */
+    pub fn get_signed_digest(&self, hash: &[u8; 32]) -> [u8; 32] {
+        let prefix: &[u8] = match self {
+            SignatureType::NoPrepend => return *hash,
+            SignatureType::Decimal => b"\x19Ethereum Signed Message:\n32",
+            SignatureType::Hexadecimal => b"\x19Ethereum Signed Message:\n\x20",
+        };
+        keccak256([prefix, hash].concat())
+    }
+}
+
+/**
+ * Splits an exchange signature (65 byte r,s,v followed by the signature type byte) into its parts
+ */
+pub fn parse_signature(signature: &str) -> Result<(Signature, SignatureType)> {
+    let bytes = decode_hex(signature, "signature")?;
+    if bytes.len() != 66 {
+        return Err(SigningError::InvalidInput(format!(
+            "signature must be 66 bytes, got {}",
+            bytes.len()
+        )));
+    }
+    let signature_type = SignatureType::try_from(bytes[65])?;
+    let signature = Signature::try_from(&bytes[..65])
+        .map_err(|e| SigningError::InvalidInput(format!("malformed signature: {}", e)))?;
+    Ok((signature, signature_type))
+}
+
+/**
+ * Given an EIP 712 hash and the exchange signature over it, returns the address that signed it
+ */
+pub fn recover_signer(eip712_hash: &str, signature: &str) -> Result<H160> {
+    let hash: [u8; 32] = decode_hex(eip712_hash, "EIP 712 hash")?
+        .try_into()
+        .map_err(|_| SigningError::InvalidInput("EIP 712 hash must be 32 bytes".to_string()))?;
+    let (signature, signature_type) = parse_signature(signature)?;
+    let digest = H256::from(signature_type.get_signed_digest(&hash));
+
+    signature
+        .recover(digest)
+        .map_err(|e| SigningError::InvalidInput(format!("signer can not be recovered: {}", e)))
+}
+
+/**
+ * Returns true if the order signature was produced by the order maker
+ */
+pub fn verify_order_signature(order: &Order, signature: &str, trader_contract: &str, chain_id: &str) -> Result<bool> {
+    let order_hash = get_hash(order, trader_contract, chain_id)?;
+    Ok(recover_signer(&order_hash, signature)? == order.maker)
+}
+
+/**
+ * Returns true if the cancel signature of the order hashes was produced by the maker
+ */
+pub fn verify_cancel_signature<S: AsRef<str>>(
+    order_hashes: &[S],
+    signature: &str,
+    maker: H160,
+    trader_contract: &str,
+    chain_id: &str,
+) -> Result<bool> {
+    let cancel_hash = get_cancel_hash(order_hashes, trader_contract, chain_id)?;
+    Ok(recover_signer(&cancel_hash, signature)? == maker)
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::signers::LocalWallet;
+
+    use super::*;
+    use crate::arb::order::sign_order;
+    use crate::Amount;
+
+    const WALLET_KEY: &str = "2ee813034aab842141cb85d477f7d0e359838f46fcab34a935c69410a4d39efb";
+
+    const TRADER_CONTRACT: &str = "0x934Dd6503795ef6EE6a36e3b3f1d7Be6c7096955";
+
+    const CHAIN_ID: &str = "421613";
+
+    const ORDER_HASH: &str = "400fbdbc69662f7bf23c57799ea6b5ff6c6c7e2742a46eccbf83b3366595bf43";
+
+    fn wallet() -> LocalWallet {
+        WALLET_KEY.parse().unwrap()
+    }
+
+    fn order() -> Order {
+        Order {
+            is_buy: true,
+            reduce_only: true,
+            quantity: "6".parse().unwrap(),
+            price: "1800".parse().unwrap(),
+            trigger_price: Amount::ZERO,
+            leverage: "0.02".parse().unwrap(),
+            expiration: 1690995498,
+            salt: 1231231231,
+            maker: "0x6f03f28bc1ebb7c9b45614bf2483e70f008a6d3d".parse().unwrap(),
+        }
+    }
+
+    /**
+     * Signs the digest the signature type stands for and appends the type byte
+     */
+    fn sign_with_type(hash: &str, signature_type: SignatureType) -> String {
+        let hash: [u8; 32] = hex::decode(hash).unwrap().try_into().unwrap();
+        let signature = wallet().sign_hash(H256::from(signature_type.get_signed_digest(&hash))).unwrap();
+        format!("0x{}{:02x}", signature, signature_type as u8)
+    }
+
+    #[tokio::test]
+    async fn verifies_order_signatures_of_the_maker() {
+        let order = order();
+        let order_hash = get_hash(&order, TRADER_CONTRACT, CHAIN_ID).unwrap();
+        let signature = sign_order(&wallet(), &order_hash).await.unwrap();
+
+        assert_eq!(recover_signer(&order_hash, &signature).unwrap(), order.maker);
+        assert!(verify_order_signature(&order, &signature, TRADER_CONTRACT, CHAIN_ID).unwrap());
+    }
+
+    #[tokio::test]
+    async fn rejects_order_signatures_of_another_maker_or_order() {
+        let order = order();
+        let signature = sign_order(&wallet(), &get_hash(&order, TRADER_CONTRACT, CHAIN_ID).unwrap()).await.unwrap();
+
+        let mut other_maker = order.clone();
+        other_maker.maker = "0x934Dd6503795ef6EE6a36e3b3f1d7Be6c7096955".parse().unwrap();
+        assert!(!verify_order_signature(&other_maker, &signature, TRADER_CONTRACT, CHAIN_ID).unwrap());
+
+        let mut tampered = order.clone();
+        tampered.price = "1801".parse().unwrap();
+        assert!(!verify_order_signature(&tampered, &signature, TRADER_CONTRACT, CHAIN_ID).unwrap());
+        assert!(!verify_order_signature(&order, &signature, TRADER_CONTRACT, "42161").unwrap());
+    }
+
+    #[tokio::test]
+    async fn verifies_cancel_signatures_over_several_hashes() {
+        let maker = order().maker;
+        let order_hashes = [
+            ORDER_HASH.to_string(),
+            format!("0x{}", "11".repeat(32)),
+            format!("0x{}", "22".repeat(32)),
+        ];
+        let cancel_hash = get_cancel_hash(&order_hashes, TRADER_CONTRACT, CHAIN_ID).unwrap();
+        let signature = sign_order(&wallet(), &cancel_hash).await.unwrap();
+
+        assert!(verify_cancel_signature(&order_hashes, &signature, maker, TRADER_CONTRACT, CHAIN_ID).unwrap());
+        // a cancel signature only covers the exact hashes it was made for
+        assert!(!verify_cancel_signature(&order_hashes[..2], &signature, maker, TRADER_CONTRACT, CHAIN_ID).unwrap());
+        let other_maker = "0x934Dd6503795ef6EE6a36e3b3f1d7Be6c7096955".parse().unwrap();
+        assert!(!verify_cancel_signature(&order_hashes, &signature, other_maker, TRADER_CONTRACT, CHAIN_ID).unwrap());
+    }
+
+    #[test]
+    fn recovers_the_signer_for_every_signature_type() {
+        let maker = order().maker;
+        for signature_type in [SignatureType::NoPrepend, SignatureType::Decimal, SignatureType::Hexadecimal] {
+            let signature = sign_with_type(ORDER_HASH, signature_type);
+            assert_eq!(parse_signature(&signature).unwrap().1, signature_type);
+            assert_eq!(recover_signer(ORDER_HASH, &signature).unwrap(), maker, "{:?}", signature_type);
+        }
+    }
+
+    #[tokio::test]
+    async fn decimal_signatures_match_sign_order() {
+        let signature = sign_order(&wallet(), ORDER_HASH).await.unwrap();
+        assert_eq!(sign_with_type(ORDER_HASH, SignatureType::Decimal), signature);
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_signatures() {
+        let signature = sign_order(&wallet(), ORDER_HASH).await.unwrap();
+
+        let unknown_type = format!("{}03", &signature[..signature.len() - 2]);
+        assert!(matches!(recover_signer(ORDER_HASH, &unknown_type), Err(SigningError::InvalidInput(_))));
+        let too_short = &signature[..signature.len() - 2];
+        assert!(matches!(recover_signer(ORDER_HASH, too_short), Err(SigningError::InvalidInput(_))));
+        let too_long = format!("{}01", signature);
+        assert!(matches!(recover_signer(ORDER_HASH, &too_long), Err(SigningError::InvalidInput(_))));
+        let not_hex = format!("0x{}", "zz".repeat(66));
+        assert!(matches!(recover_signer(ORDER_HASH, &not_hex), Err(SigningError::InvalidInput(_))));
+        assert!(matches!(recover_signer("0x1234", &signature), Err(SigningError::InvalidInput(_))));
+        assert!(verify_order_signature(&order(), &unknown_type, TRADER_CONTRACT, CHAIN_ID).is_err());
+    }
+}
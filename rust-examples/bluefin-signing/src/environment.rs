@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use crate::{Result, SigningError};
+
+/**
+ * A Bluefin deployment: where its REST API lives, the url signed to onboard, the chain id used in
+ * EIP-712 domains (Arbitrum only) and named contract addresses, such as the trader contract of each
+ * Arbitrum market keyed by symbol.
+ * Use one of the presets, a JSON config file with the same fields, or environment variables
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Environment {
+    pub name: String,
+    pub api_url: String,
+    pub onboarding_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub contracts: BTreeMap<String, String>,
+}
+
+impl Environment {
+    /**
+     * Names of the built in deployments accepted by `Environment::preset`
+     */
+    pub const PRESETS: [&'static str; 4] = ["arb-mainnet", "arb-testnet", "sui-mainnet", "sui-staging"];
+
+    /**
+     * The Arbitrum mainnet deployment, without trader contracts: signing orders needs a `BLUEFIN_CONFIG`
+     * file listing them under `contracts`, or the contract address passed explicitly
+     */
+    pub fn arb_mainnet() -> Self {
+        Environment {
+            name: "arb-mainnet".to_string(),
+            api_url: "https://dapi.firefly.exchange".to_string(),
+            onboarding_url: "https://trade-arb.firefly.exchange".to_string(),
+            chain_id: Some("42161".to_string()),
+            contracts: BTreeMap::new(),
+        }
+    }
+
+    /**
+     * The Arbitrum Goerli testnet deployment, with the hosts and chain id of the testnet network
+     * in the Firefly python client (`Networks` in firefly_exchange_client/constants.py)
+     */
+    pub fn arb_testnet() -> Self {
+        Environment {
+            name: "arb-testnet".to_string(),
+            api_url: "https://dapi-testnet.firefly.exchange".to_string(),
+            onboarding_url: "https://testnet.firefly.exchange".to_string(),
+            chain_id: Some("421613".to_string()),
+            contracts: BTreeMap::from([(
+                "ETH-PERP".to_string(),
+                "0x934Dd6503795ef6EE6a36e3b3f1d7Be6c7096955".to_string(),
+            )]),
+        }
+    }
+
+    pub fn sui_mainnet() -> Self {
+        Environment {
+            name: "sui-mainnet".to_string(),
+            api_url: "https://dapi.api.sui-prod.bluefin.io".to_string(),
+            onboarding_url: "https://trade-sui.bluefin.exchange".to_string(),
+            chain_id: None,
+            contracts: BTreeMap::new(),
+        }
+    }
+
+    /**
+     * The Sui staging deployment, which is the one Bluefin runs against the Sui testnet
+     */
+    pub fn sui_staging() -> Self {
+        Environment {
+            name: "sui-staging".to_string(),
+            api_url: "https://dapi.api.sui-staging.bluefin.io".to_string(),
+            onboarding_url: "https://testnet.bluefin.io".to_string(),
+            chain_id: None,
+            contracts: BTreeMap::new(),
+        }
+    }
+
+    /**
+     * Returns a built in deployment by name, one of `Environment::PRESETS`
+     */
+    pub fn preset(name: &str) -> Result<Self> {
+        match name {
+            "arb-mainnet" => Ok(Environment::arb_mainnet()),
+            "arb-testnet" => Ok(Environment::arb_testnet()),
+            "sui-mainnet" => Ok(Environment::sui_mainnet()),
+            "sui-staging" => Ok(Environment::sui_staging()),
+            _ => Err(SigningError::InvalidInput(format!(
+                "unknown environment {}, expected one of {}",
+                name,
+                Environment::PRESETS.join(", ")
+            ))),
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /**
+     * Loads a custom deployment from a JSON config file
+     */
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .map_err(|e| SigningError::InvalidInput(format!("can not read {}: {}", path.display(), e)))?;
+        Environment::from_json(&json)
+    }
+
+    /**
+     * Selects the deployment from environment variables, falling back to `default`:
+     * `BLUEFIN_CONFIG` names a JSON config file, otherwise `BLUEFIN_ENV` names a preset.
+     * `BLUEFIN_API_URL`, `BLUEFIN_ONBOARDING_URL` and `BLUEFIN_CHAIN_ID` then override single fields
+     */
+    pub fn from_env(default: Environment) -> Result<Self> {
+        let mut environment = match (env::var("BLUEFIN_CONFIG").ok(), env::var("BLUEFIN_ENV").ok()) {
+            (Some(path), _) => Environment::load(path)?,
+            (None, Some(name)) => Environment::preset(&name)?,
+            (None, None) => default,
+        };
+
+        if let Ok(api_url) = env::var("BLUEFIN_API_URL") {
+            environment.api_url = api_url;
+        }
+        if let Ok(onboarding_url) = env::var("BLUEFIN_ONBOARDING_URL") {
+            environment.onboarding_url = onboarding_url;
+        }
+        if let Ok(chain_id) = env::var("BLUEFIN_CHAIN_ID") {
+            environment.chain_id = Some(chain_id);
+        }
+        Ok(environment)
+    }
+
+    /**
+     * Returns the chain id, which only Arbitrum deployments have
+     */
+    pub fn chain_id(&self) -> Result<&str> {
+        self.chain_id
+            .as_deref()
+            .ok_or_else(|| SigningError::InvalidInput(format!("environment {} has no chain id", self.name)))
+    }
+
+    /**
+     * Returns a named contract address, such as the trader contract of a market
+     */
+    pub fn contract(&self, name: &str) -> Result<&str> {
+        self.contracts
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| SigningError::InvalidInput(format!("environment {} has no {} contract", self.name, name)))
+    }
+
+    /**
+     * Returns the url of an API route such as "/orders"
+     */
+    pub fn api_route(&self, route: &str) -> String {
+        format!("{}{}", self.api_url.trim_end_matches('/'), route)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /**
+     * Serializes the tests reading the process environment, which is shared by every test thread
+     */
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    const VARIABLES: [&str; 5] = ["BLUEFIN_CONFIG", "BLUEFIN_ENV", "BLUEFIN_API_URL", "BLUEFIN_ONBOARDING_URL", "BLUEFIN_CHAIN_ID"];
+
+    /**
+     * Runs `from_env` with exactly the given variables set
+     */
+    fn from_env(variables: &[(&str, &str)]) -> Result<Environment> {
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for name in VARIABLES {
+            env::remove_var(name);
+        }
+        for (name, value) in variables {
+            env::set_var(name, value);
+        }
+        let environment = Environment::from_env(Environment::sui_staging());
+        for name in VARIABLES {
+            env::remove_var(name);
+        }
+        environment
+    }
+
+    fn config_file(name: &str, environment: &Environment) -> PathBuf {
+        let path = env::temp_dir().join(format!("bluefin-{}-{}.json", name, std::process::id()));
+        fs::write(&path, serde_json::to_string(environment).unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn every_preset_is_named_after_itself() {
+        for name in Environment::PRESETS {
+            let environment = Environment::preset(name).unwrap();
+            assert_eq!(environment.name, name);
+            assert!(environment.api_url.starts_with("https://"), "{}", name);
+            assert!(environment.onboarding_url.starts_with("https://"), "{}", name);
+            assert_eq!(environment.chain_id.is_some(), name.starts_with("arb-"), "{}", name);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_presets() {
+        for name in ["sui-testnet", "ARB-MAINNET", ""] {
+            assert!(matches!(Environment::preset(name), Err(SigningError::InvalidInput(_))), "{}", name);
+        }
+        assert!(from_env(&[("BLUEFIN_ENV", "sui-testnet")]).is_err());
+    }
+
+    #[test]
+    fn falls_back_to_the_default() {
+        assert_eq!(from_env(&[]).unwrap(), Environment::sui_staging());
+    }
+
+    #[test]
+    fn bluefin_env_names_a_preset() {
+        assert_eq!(from_env(&[("BLUEFIN_ENV", "arb-testnet")]).unwrap(), Environment::arb_testnet());
+    }
+
+    #[test]
+    fn bluefin_config_wins_over_bluefin_env() {
+        let mut custom = Environment::arb_testnet();
+        custom.name = "custom".to_string();
+        custom.api_url = "http://localhost:8080".to_string();
+        let path = config_file("config", &custom);
+
+        let environment = from_env(&[("BLUEFIN_CONFIG", path.to_str().unwrap()), ("BLUEFIN_ENV", "sui-mainnet")]);
+        assert_eq!(environment.unwrap(), custom);
+        assert!(from_env(&[("BLUEFIN_CONFIG", "/nonexistent/bluefin.json"), ("BLUEFIN_ENV", "sui-mainnet")]).is_err());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn variables_override_single_fields() {
+        let environment = from_env(&[
+            ("BLUEFIN_ENV", "arb-testnet"),
+            ("BLUEFIN_API_URL", "http://localhost:8080"),
+            ("BLUEFIN_ONBOARDING_URL", "http://localhost:3000"),
+            ("BLUEFIN_CHAIN_ID", "31337"),
+        ])
+        .unwrap();
+        assert_eq!(environment.name, "arb-testnet");
+        assert_eq!(environment.api_url, "http://localhost:8080");
+        assert_eq!(environment.onboarding_url, "http://localhost:3000");
+        assert_eq!(environment.chain_id().unwrap(), "31337");
+        assert_eq!(environment.contracts, Environment::arb_testnet().contracts);
+
+        // the chain id override also applies to Sui deployments, which have none of their own
+        let environment = from_env(&[("BLUEFIN_CHAIN_ID", "31337")]).unwrap();
+        assert_eq!(environment.chain_id().unwrap(), "31337");
+        assert_eq!(environment.api_url, Environment::sui_staging().api_url);
+    }
+
+    #[test]
+    fn arb_mainnet_needs_trader_contracts_from_config() {
+        assert!(matches!(Environment::arb_mainnet().contract("ETH-PERP"), Err(SigningError::InvalidInput(_))));
+        assert_eq!(
+            Environment::arb_testnet().contract("ETH-PERP").unwrap(),
+            "0x934Dd6503795ef6EE6a36e3b3f1d7Be6c7096955"
+        );
+        assert!(Environment::sui_staging().chain_id().is_err());
+        assert_eq!(Environment::arb_mainnet().chain_id().unwrap(), "42161");
+    }
+
+    #[test]
+    fn joins_api_routes() {
+        let mut environment = Environment::sui_staging();
+        assert_eq!(environment.api_route("/orders"), "https://dapi.api.sui-staging.bluefin.io/orders");
+        environment.api_url.push('/');
+        assert_eq!(environment.api_route("/orders"), "https://dapi.api.sui-staging.bluefin.io/orders");
+    }
+}
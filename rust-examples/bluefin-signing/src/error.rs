@@ -0,0 +1,70 @@
+use std::fmt;
+
+/**
+ * Errors returned while hashing, signing or posting Bluefin messages
+ */
+#[derive(Debug)]
+pub enum SigningError {
+    /// A value could not be parsed: bad hex, decimal, address or JSON
+    InvalidInput(String),
+    /// A value does not fit the width it is encoded into
+    Overflow(String),
+    /// The private key could not be loaded or used for signing
+    Key(String),
+    /// The HTTP request to the exchange could not be completed
+    Transport(reqwest::Error),
+    /// The exchange answered with an error status or an unexpected body, `code` is the exchange error code if it sent one
+    Exchange {
+        status: u16,
+        code: Option<i64>,
+        message: String,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, SigningError>;
+
+impl fmt::Display for SigningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SigningError::InvalidInput(msg) => write!(f, "invalid input: {}", msg),
+            SigningError::Overflow(msg) => write!(f, "encoding overflow: {}", msg),
+            SigningError::Key(msg) => write!(f, "key error: {}", msg),
+            SigningError::Transport(err) => write!(f, "transport error: {}", err),
+            SigningError::Exchange {
+                status,
+                code: Some(code),
+                message,
+            } => write!(f, "exchange rejected request ({}, code {}): {}", status, code, message),
+            SigningError::Exchange { status, message, .. } => {
+                write!(f, "exchange rejected request ({}): {}", status, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SigningError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SigningError::Transport(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for SigningError {
+    fn from(err: reqwest::Error) -> Self {
+        SigningError::Transport(err)
+    }
+}
+
+impl From<hex::FromHexError> for SigningError {
+    fn from(err: hex::FromHexError) -> Self {
+        SigningError::InvalidInput(format!("bad hex: {}", err))
+    }
+}
+
+impl From<serde_json::Error> for SigningError {
+    fn from(err: serde_json::Error) -> Self {
+        SigningError::InvalidInput(format!("bad json: {}", err))
+    }
+}
@@ -0,0 +1,23 @@
+//! Off-chain signing for the Bluefin exchanges.
+//!
+//! The `arb` feature covers the Firefly/Bluefin Arbitrum deployment (EIP-712 orders
+//! signed with an Ethereum wallet), the `sui` feature covers Bluefin on Sui (BCS
+//! serialized orders signed with an Ed25519 key). Both are enabled by default.
+
+pub mod api;
+mod amount;
+mod environment;
+mod error;
+mod order_type;
+pub mod transport;
+
+pub use amount::Amount;
+pub use environment::Environment;
+pub use error::{Result, SigningError};
+pub use order_type::{OrderKind, OrderType, Side, TimeInForce};
+
+#[cfg(feature = "arb")]
+pub mod arb;
+
+#[cfg(feature = "sui")]
+pub mod sui;
@@ -0,0 +1,228 @@
+//! Order type, time in force and side as the Bluefin REST APIs name them, shared by Arbitrum and Sui.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{Result, SigningError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderType {
+    Market,
+    Limit,
+    StopMarket,
+    StopLimit,
+}
+
+impl OrderType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderType::Market => "MARKET",
+            OrderType::Limit => "LIMIT",
+            OrderType::StopMarket => "STOP_MARKET",
+            OrderType::StopLimit => "STOP_LIMIT",
+        }
+    }
+}
+
+impl fmt::Display for OrderType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TimeInForce {
+    /// good till time, rests on the book until the order expiration
+    Gtt,
+    /// immediate or cancel, whatever does not fill right away is cancelled
+    Ioc,
+    /// fill or kill, the order fills completely right away or not at all
+    Fok,
+}
+
+impl TimeInForce {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeInForce::Gtt => "GTT",
+            TimeInForce::Ioc => "IOC",
+            TimeInForce::Fok => "FOK",
+        }
+    }
+}
+
+impl fmt::Display for TimeInForce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Side::Buy => "BUY",
+            Side::Sell => "SELL",
+        }
+    }
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/**
+ * The fields of an order that its type and time in force constrain
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderKind {
+    pub order_type: OrderType,
+    pub time_in_force: TimeInForce,
+    pub post_only: bool,
+    pub has_price: bool,
+    pub has_trigger_price: bool,
+}
+
+impl OrderKind {
+    /**
+     * Rejects combinations the exchange refuses: post only orders that are not GTT limit orders,
+     * limit orders without a price, and trigger prices missing on stop orders or set on any other
+     */
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| Err(SigningError::InvalidInput(format!("invalid order: {}", reason)));
+
+        if self.post_only && self.time_in_force != TimeInForce::Gtt {
+            return invalid(format!("post only orders can not be {}", self.time_in_force));
+        }
+        if self.post_only && matches!(self.order_type, OrderType::Market | OrderType::StopMarket) {
+            return invalid(format!("post only orders can not be {}", self.order_type));
+        }
+        if !self.has_price && matches!(self.order_type, OrderType::Limit | OrderType::StopLimit) {
+            return invalid(format!("{} orders need a price", self.order_type));
+        }
+        let is_stop = matches!(self.order_type, OrderType::StopMarket | OrderType::StopLimit);
+        if is_stop && !self.has_trigger_price {
+            return invalid(format!("{} orders need a trigger price", self.order_type));
+        }
+        if !is_stop && self.has_trigger_price {
+            return invalid(format!("{} orders can not have a trigger price", self.order_type));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORDER_TYPES: [OrderType; 4] = [OrderType::Market, OrderType::Limit, OrderType::StopMarket, OrderType::StopLimit];
+
+    const TIMES_IN_FORCE: [TimeInForce; 3] = [TimeInForce::Gtt, TimeInForce::Ioc, TimeInForce::Fok];
+
+    /**
+     * A valid kind of the given type: priced unless a market order, triggered if a stop order
+     */
+    fn kind(order_type: OrderType) -> OrderKind {
+        OrderKind {
+            order_type,
+            time_in_force: TimeInForce::Gtt,
+            post_only: false,
+            has_price: matches!(order_type, OrderType::Limit | OrderType::StopLimit),
+            has_trigger_price: matches!(order_type, OrderType::StopMarket | OrderType::StopLimit),
+        }
+    }
+
+    fn is_invalid(kind: OrderKind) -> bool {
+        matches!(kind.validate(), Err(SigningError::InvalidInput(_)))
+    }
+
+    #[test]
+    fn accepts_every_type_in_every_time_in_force() {
+        for order_type in ORDER_TYPES {
+            for time_in_force in TIMES_IN_FORCE {
+                let kind = OrderKind { time_in_force, ..kind(order_type) };
+                assert!(kind.validate().is_ok(), "{:?}", kind);
+            }
+        }
+        let market_with_price = OrderKind { has_price: true, ..kind(OrderType::Market) };
+        assert!(market_with_price.validate().is_ok());
+    }
+
+    #[test]
+    fn post_only_orders_must_be_gtt_limit_orders() {
+        for order_type in [OrderType::Limit, OrderType::StopLimit] {
+            assert!(OrderKind { post_only: true, ..kind(order_type) }.validate().is_ok());
+            for time_in_force in [TimeInForce::Ioc, TimeInForce::Fok] {
+                assert!(is_invalid(OrderKind { post_only: true, time_in_force, ..kind(order_type) }));
+            }
+        }
+        for order_type in [OrderType::Market, OrderType::StopMarket] {
+            assert!(is_invalid(OrderKind { post_only: true, ..kind(order_type) }));
+            assert!(is_invalid(OrderKind { post_only: true, has_price: true, ..kind(order_type) }));
+        }
+    }
+
+    #[test]
+    fn limit_orders_need_a_price() {
+        for order_type in [OrderType::Limit, OrderType::StopLimit] {
+            assert!(is_invalid(OrderKind { has_price: false, ..kind(order_type) }));
+        }
+    }
+
+    #[test]
+    fn only_stop_orders_have_a_trigger_price() {
+        for order_type in [OrderType::StopMarket, OrderType::StopLimit] {
+            assert!(is_invalid(OrderKind { has_trigger_price: false, ..kind(order_type) }));
+        }
+        for order_type in [OrderType::Market, OrderType::Limit] {
+            assert!(is_invalid(OrderKind { has_trigger_price: true, ..kind(order_type) }));
+        }
+    }
+
+    #[test]
+    fn serializes_the_rest_api_names() {
+        let names = [
+            (serde_json::to_value(OrderType::Market).unwrap(), OrderType::Market.as_str(), "MARKET"),
+            (serde_json::to_value(OrderType::Limit).unwrap(), OrderType::Limit.as_str(), "LIMIT"),
+            (serde_json::to_value(OrderType::StopMarket).unwrap(), OrderType::StopMarket.as_str(), "STOP_MARKET"),
+            (serde_json::to_value(OrderType::StopLimit).unwrap(), OrderType::StopLimit.as_str(), "STOP_LIMIT"),
+            (serde_json::to_value(TimeInForce::Gtt).unwrap(), TimeInForce::Gtt.as_str(), "GTT"),
+            (serde_json::to_value(TimeInForce::Ioc).unwrap(), TimeInForce::Ioc.as_str(), "IOC"),
+            (serde_json::to_value(TimeInForce::Fok).unwrap(), TimeInForce::Fok.as_str(), "FOK"),
+            (serde_json::to_value(Side::Buy).unwrap(), Side::Buy.as_str(), "BUY"),
+            (serde_json::to_value(Side::Sell).unwrap(), Side::Sell.as_str(), "SELL"),
+        ];
+        for (json, as_str, name) in names {
+            assert_eq!(json, name);
+            assert_eq!(as_str, name);
+        }
+
+        for order_type in ORDER_TYPES {
+            let json = serde_json::to_string(&order_type).unwrap();
+            assert_eq!(serde_json::from_str::<OrderType>(&json).unwrap(), order_type);
+            assert_eq!(json, format!("\"{}\"", order_type));
+        }
+        for time_in_force in TIMES_IN_FORCE {
+            let json = serde_json::to_string(&time_in_force).unwrap();
+            assert_eq!(serde_json::from_str::<TimeInForce>(&json).unwrap(), time_in_force);
+            assert_eq!(json, format!("\"{}\"", time_in_force));
+        }
+        for side in [Side::Buy, Side::Sell] {
+            let json = serde_json::to_string(&side).unwrap();
+            assert_eq!(serde_json::from_str::<Side>(&json).unwrap(), side);
+            assert_eq!(json, format!("\"{}\"", side));
+        }
+        assert!(serde_json::from_str::<OrderType>("\"limit\"").is_err());
+        assert!(serde_json::from_str::<TimeInForce>("\"GTC\"").is_err());
+        assert!(serde_json::from_str::<Side>("\"Buy\"").is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use blake2b_simd::Params;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+use super::signature::SignatureScheme;
+use crate::{Result, SigningError};
+
+/**
+ * A 32 byte Sui account address (also the format of object ids such as a market's perpetual id)
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SuiAddress([u8; SuiAddress::LENGTH]);
+
+impl SuiAddress {
+    pub const LENGTH: usize = 32;
+
+    pub fn new(bytes: [u8; SuiAddress::LENGTH]) -> Self {
+        SuiAddress(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes: [u8; SuiAddress::LENGTH] = bytes.try_into().map_err(|_| {
+            SigningError::InvalidInput(format!("sui address must be 32 bytes, got {}", bytes.len()))
+        })?;
+        Ok(SuiAddress(bytes))
+    }
+
+    /**
+     * Derives the address of a public key: the Blake2b-256 hash of the scheme flag followed by the public key
+     */
+    pub fn from_public_key(scheme: SignatureScheme, public_key: &[u8]) -> Result<Self> {
+        if public_key.len() != scheme.public_key_length() {
+            return Err(SigningError::InvalidInput(format!(
+                "{:?} public key must be {} bytes, got {}",
+                scheme,
+                scheme.public_key_length(),
+                public_key.len()
+            )));
+        }
+
+        let hash = Params::new()
+            .hash_length(SuiAddress::LENGTH)
+            .to_state()
+            .update(&[scheme.flag()])
+            .update(public_key)
+            .finalize();
+        SuiAddress::from_bytes(hash.as_bytes())
+    }
+
+    pub fn as_bytes(&self) -> &[u8; SuiAddress::LENGTH] {
+        &self.0
+    }
+
+    /**
+     * Returns the full 0x prefixed, lowercase, 64 character hex form
+     */
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.0))
+    }
+}
+
+/**
+ * Parses 0x prefixed or bare hex, short forms such as `0x2` are left padded with zeros
+ */
+impl FromStr for SuiAddress {
+    type Err = SigningError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let trimmed = value.trim();
+        let digits = trimmed
+            .strip_prefix("0x")
+            .or_else(|| trimmed.strip_prefix("0X"))
+            .unwrap_or(trimmed);
+
+        if digits.is_empty() || digits.len() > SuiAddress::LENGTH * 2 {
+            return Err(SigningError::InvalidInput(format!("invalid sui address: {}", value)));
+        }
+
+        let padded = format!("{:0>64}", digits);
+        let bytes = hex::decode(padded)
+            .map_err(|e| SigningError::InvalidInput(format!("invalid sui address {}: {}", value, e)))?;
+        SuiAddress::from_bytes(&bytes)
+    }
+}
+
+impl fmt::Display for SuiAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl fmt::Debug for SuiAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SuiAddress({})", self.to_hex())
+    }
+}
+
+impl Serialize for SuiAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for SuiAddress {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "0xc6c71c996d437eb6589d1b8b17afcd1480afd5f30f6b7155ef468a9713d3240e";
+
+    fn parse(value: &str) -> Result<SuiAddress> {
+        value.parse()
+    }
+
+    #[test]
+    fn pads_short_forms_with_zeros() {
+        let mut bytes = [0u8; SuiAddress::LENGTH];
+        bytes[31] = 2;
+        for value in ["0x2", "2", "0x02", " 0x2 ", "0X2"] {
+            assert_eq!(parse(value).unwrap(), SuiAddress::new(bytes), "{}", value);
+        }
+        bytes[30] = 0x01;
+        bytes[31] = 0x23;
+        assert_eq!(parse("0x123").unwrap(), SuiAddress::new(bytes));
+    }
+
+    #[test]
+    fn parses_full_addresses_in_any_case() {
+        let address = parse(ADDRESS).unwrap();
+        assert_eq!(parse(&ADDRESS.to_uppercase()).unwrap(), address);
+        assert_eq!(parse(ADDRESS.trim_start_matches("0x")).unwrap(), address);
+        assert_eq!(address.to_hex(), ADDRESS);
+    }
+
+    #[test]
+    fn rejects_empty_too_long_and_non_hex_input() {
+        for value in ["", "0x", "0X", &format!("{}0", ADDRESS), &format!("0x{}", "1".repeat(65)), "0xg1", "0x-1", "sui", "0x 1"] {
+            assert!(matches!(parse(value), Err(SigningError::InvalidInput(_))), "{}", value);
+        }
+    }
+
+    #[test]
+    fn displays_and_serializes_the_full_hex_form() {
+        let address = parse("0x3").unwrap();
+        let full = format!("0x{:0>64}", "3");
+        assert_eq!(address.to_string(), full);
+        assert_eq!(format!("{:?}", address), format!("SuiAddress({})", full));
+        assert_eq!(address.to_string().parse::<SuiAddress>().unwrap(), address);
+
+        let json = serde_json::to_string(&address).unwrap();
+        assert_eq!(json, format!("\"{}\"", full));
+        assert_eq!(serde_json::from_str::<SuiAddress>(&json).unwrap(), address);
+        assert_eq!(serde_json::from_str::<SuiAddress>("\"0x3\"").unwrap(), address);
+        assert!(serde_json::from_str::<SuiAddress>("\"0xzz\"").is_err());
+        assert!(serde_json::from_str::<SuiAddress>("3").is_err());
+    }
+
+    #[test]
+    fn rejects_byte_slices_of_the_wrong_length() {
+        assert!(SuiAddress::from_bytes(&[0; 31]).is_err());
+        assert!(SuiAddress::from_bytes(&[0; 33]).is_err());
+        assert_eq!(SuiAddress::from_bytes(&[7; 32]).unwrap().as_bytes(), &[7; 32]);
+    }
+}
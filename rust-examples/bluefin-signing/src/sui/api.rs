@@ -0,0 +1,320 @@
+use serde::{Deserialize, Serialize};
+
+use super::address::SuiAddress;
+use super::keypair::SuiKeyPair;
+use super::order::{sign_cancel_orders, validate_order, Order, OrderType, Side, TimeInForce};
+use super::signature::BluefinSuiSignature;
+pub use crate::api::{AuthorizeResponse, CancelOrdersResponse, OrderResponse, MAX_CANCEL_ORDER_HASHES};
+use crate::{Result, SigningError};
+
+/**
+ * Body of the /authorize route, exchanging an onboarding signature for a JWT token
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizeRequest {
+    pub signature: String,
+    pub user_address: String,
+    /// sent as the string "True"
+    pub is_term_accepted: String,
+}
+
+impl AuthorizeRequest {
+    pub fn new(onboarding_signature: &BluefinSuiSignature, user_address: &SuiAddress) -> Result<Self> {
+        Ok(AuthorizeRequest {
+            signature: onboarding_signature.to_wire_format()?,
+            user_address: user_address.to_hex(),
+            is_term_accepted: "True".to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderJSONRequest {
+    pub orderbook_only: bool,
+    pub symbol: String,
+    pub price: String,
+    pub quantity: String,
+    pub trigger_price: String,
+    pub leverage: String,
+    pub user_address: String,
+    pub order_type: OrderType,
+    pub side: Side,
+    pub reduce_only: bool,
+    pub salt: u128,
+    pub expiration: u128,
+    pub order_signature: String,
+    pub time_in_force: TimeInForce,
+    pub post_only: bool,
+    pub cancel_on_revert: bool,
+    pub client_id: String,
+}
+
+impl OrderJSONRequest {
+    /**
+     * Creates the request placing a signed order. The order is placed for its maker,
+     * which is the parent account when a sub-account signed it
+     */
+    pub fn new(order: &Order, order_signature: &BluefinSuiSignature) -> Result<Self> {
+        validate_order(order)?;
+
+        Ok(OrderJSONRequest {
+            orderbook_only: order.orderbook_only,
+            symbol: order.market.to_string(),
+            price: order.price.to_string(),
+            quantity: order.quantity.to_string(),
+            trigger_price: order.trigger_price.to_string(),
+            leverage: order.leverage.to_string(),
+            user_address: order.maker.to_string(),
+            order_type: order.order_type,
+            side: order.side,
+            reduce_only: order.reduce_only,
+            salt: order.salt,
+            expiration: order.expiration,
+            order_signature: order_signature.to_wire_format()?,
+            time_in_force: order.time_in_force,
+            post_only: order.post_only,
+            cancel_on_revert: false,
+            client_id: "bluefin-v2-client-python".to_string(),
+        })
+    }
+}
+
+/**
+ * Cancellation of orders, signed as a whole. When a sub-account cancels orders of its parent, the cancel signature
+ * comes from the sub-account and `parent_address` is the parent, otherwise it is empty
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderCancellationJSONRequest {
+    pub symbol: String,
+    pub order_hashes: Vec<String>,
+    pub parent_address: String,
+    pub cancel_signature: String,
+}
+
+impl OrderCancellationJSONRequest {
+    /**
+     * Creates the request from the signature of exactly these order hashes, as made by `sign_cancel_orders`
+     */
+    pub fn new(
+        symbol: &str,
+        order_hashes: &[&str],
+        cancel_signature: &BluefinSuiSignature,
+        parent_address: Option<&SuiAddress>,
+    ) -> Result<Self> {
+        Ok(OrderCancellationJSONRequest {
+            symbol: symbol.to_string(),
+            order_hashes: order_hashes.iter().map(|order_hash| order_hash.to_string()).collect(),
+            parent_address: parent_address.map(SuiAddress::to_hex).unwrap_or_default(),
+            cancel_signature: cancel_signature.to_wire_format()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerpetualAddress {
+    pub id: String,
+}
+
+/**
+ * Market metadata returned by the /meta route, only the fields used here are decoded
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketMeta {
+    pub perpetual_address: PerpetualAddress,
+}
+
+/**
+ * Signs the cancellation of any number of order hashes, split into requests of at most `max_order_hashes`
+ * hashes each, every request carrying the signature of its own hashes
+ */
+pub fn create_cancel_requests(
+    keypair: &SuiKeyPair,
+    symbol: &str,
+    order_hashes: &[&str],
+    parent_address: Option<&SuiAddress>,
+    max_order_hashes: usize,
+) -> Result<Vec<OrderCancellationJSONRequest>> {
+    if order_hashes.is_empty() {
+        return Err(SigningError::InvalidInput("at least one order hash is required".to_string()));
+    }
+    if max_order_hashes == 0 {
+        return Err(SigningError::InvalidInput("max_order_hashes must be at least 1".to_string()));
+    }
+    order_hashes
+        .chunks(max_order_hashes)
+        .map(|chunk| {
+            let cancel_signature = sign_cancel_orders(keypair, chunk.to_vec());
+            OrderCancellationJSONRequest::new(symbol, chunk, &cancel_signature, parent_address)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sui::order::{get_serialized_order, sign_order};
+    use crate::sui::{verify_sui_cancel_signature, MarketRegistry, SignatureScheme};
+
+    const WALLET_KEY: &str = "c501312ca9eb1aaac6344edbe160e41d3d8d79570e6440f2a84f7d9abf462270";
+
+    const TRIGGER_PRICE: u128 = 1_700_000_000_000_000_000_000;
+
+    fn keypair() -> SuiKeyPair {
+        SuiKeyPair::from_hex(SignatureScheme::Ed25519, WALLET_KEY).unwrap()
+    }
+
+    fn markets() -> MarketRegistry {
+        let mut markets = MarketRegistry::new();
+        markets.insert("ETH-PERP", "0x3".parse().unwrap());
+        markets
+    }
+
+    fn order(order_type: OrderType, trigger_price: u128) -> Order {
+        Order {
+            market: "ETH-PERP".to_string(),
+            price: if order_type == OrderType::StopMarket { 0 } else { 1_800_000_000_000_000_000_000 },
+            trigger_price,
+            side: Side::Sell,
+            reduce_only: true,
+            quantity: 10_000_000_000_000_000,
+            post_only: false,
+            orderbook_only: true,
+            leverage: 3_000_000_000_000_000_000,
+            expiration: 1696489993397,
+            salt: 1695466663327515,
+            maker: keypair().address(),
+            ioc: false,
+            order_type,
+            time_in_force: TimeInForce::Gtt,
+        }
+    }
+
+    /**
+     * Serializes, signs and builds the REST payload of an order, the way the examples place orders
+     */
+    fn order_request(order: &Order) -> Result<OrderJSONRequest> {
+        let serialized_order = get_serialized_order(order, &markets())?;
+        let signature = sign_order(&keypair(), &serialized_order)?;
+        OrderJSONRequest::new(order, &signature)
+    }
+
+    #[test]
+    fn stop_orders_send_their_trigger_price() {
+        for order_type in [OrderType::StopLimit, OrderType::StopMarket] {
+            let request = order_request(&order(order_type, TRIGGER_PRICE)).unwrap();
+            let json = serde_json::to_value(&request).unwrap();
+            assert_eq!(json["triggerPrice"], "1700000000000000000000", "{}", order_type);
+            assert_eq!(json["orderType"], order_type.as_str());
+        }
+    }
+
+    #[test]
+    fn other_orders_send_a_zero_trigger_price() {
+        for order_type in [OrderType::Limit, OrderType::Market] {
+            let request = order_request(&order(order_type, 0)).unwrap();
+            assert_eq!(serde_json::to_value(&request).unwrap()["triggerPrice"], "0");
+        }
+    }
+
+    #[test]
+    fn the_trigger_price_is_not_signed() {
+        let stop_limit = order(OrderType::StopLimit, TRIGGER_PRICE);
+        let limit = order(OrderType::Limit, 0);
+        assert_eq!(
+            get_serialized_order(&stop_limit, &markets()).unwrap(),
+            get_serialized_order(&limit, &markets()).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_missing_or_extra_trigger_prices_before_signing() {
+        let invalid = [
+            order(OrderType::StopLimit, 0),
+            order(OrderType::StopMarket, 0),
+            order(OrderType::Limit, TRIGGER_PRICE),
+            order(OrderType::Market, TRIGGER_PRICE),
+        ];
+        for order in invalid {
+            assert!(matches!(get_serialized_order(&order, &markets()), Err(SigningError::InvalidInput(_))));
+            // a signature made anyway, over the order with a valid trigger, is not enough to build the payload
+            let mut signed = order.clone();
+            signed.trigger_price = if signed.trigger_price == 0 { TRIGGER_PRICE } else { 0 };
+            let signature = sign_order(&keypair(), &get_serialized_order(&signed, &markets()).unwrap()).unwrap();
+            assert!(matches!(OrderJSONRequest::new(&order, &signature), Err(SigningError::InvalidInput(_))));
+        }
+    }
+
+    fn order_hashes(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("0x{:064x}", i)).collect()
+    }
+
+    /**
+     * Splits the hashes into requests and checks every request carries the signature of exactly its own hashes
+     */
+    fn cancel_requests(count: usize) -> Vec<OrderCancellationJSONRequest> {
+        let order_hashes = order_hashes(count);
+        let order_hashes: Vec<&str> = order_hashes.iter().map(String::as_str).collect();
+        let requests = create_cancel_requests(&keypair(), "ETH-PERP", &order_hashes, None, MAX_CANCEL_ORDER_HASHES).unwrap();
+
+        let sent: Vec<&str> = requests.iter().flat_map(|request| request.order_hashes.iter().map(String::as_str)).collect();
+        assert_eq!(sent, order_hashes);
+        for request in &requests {
+            let chunk: Vec<&str> = request.order_hashes.iter().map(String::as_str).collect();
+            let signature: BluefinSuiSignature = request.cancel_signature.parse().unwrap();
+            assert!(verify_sui_cancel_signature(&chunk, &signature, &keypair().address()));
+            assert!(!verify_sui_cancel_signature(&chunk[1..], &signature, &keypair().address()));
+            if requests.len() > 1 {
+                assert!(!verify_sui_cancel_signature(&order_hashes, &signature, &keypair().address()));
+            }
+            assert_eq!(request.symbol, "ETH-PERP");
+            assert_eq!(request.parent_address, "");
+        }
+        requests
+    }
+
+    fn chunk_sizes(requests: &[OrderCancellationJSONRequest]) -> Vec<usize> {
+        requests.iter().map(|request| request.order_hashes.len()).collect()
+    }
+
+    #[test]
+    fn cancels_fit_in_one_request_up_to_the_limit() {
+        assert_eq!(chunk_sizes(&cancel_requests(1)), vec![1]);
+        assert_eq!(chunk_sizes(&cancel_requests(MAX_CANCEL_ORDER_HASHES)), vec![MAX_CANCEL_ORDER_HASHES]);
+    }
+
+    #[test]
+    fn cancels_above_the_limit_are_split() {
+        assert_eq!(chunk_sizes(&cancel_requests(MAX_CANCEL_ORDER_HASHES + 1)), vec![MAX_CANCEL_ORDER_HASHES, 1]);
+        assert_eq!(
+            chunk_sizes(&cancel_requests(MAX_CANCEL_ORDER_HASHES * 2 + 3)),
+            vec![MAX_CANCEL_ORDER_HASHES, MAX_CANCEL_ORDER_HASHES, 3]
+        );
+    }
+
+    #[test]
+    fn cancel_requests_carry_the_parent_address() {
+        let parent: SuiAddress = "0x3".parse().unwrap();
+        let requests = create_cancel_requests(&keypair(), "ETH-PERP", &["0x1", "0x2"], Some(&parent), 1).unwrap();
+        assert_eq!(chunk_sizes(&requests), vec![1, 1]);
+        for request in requests {
+            assert_eq!(request.parent_address, parent.to_hex());
+        }
+    }
+
+    #[test]
+    fn rejects_no_hashes_and_empty_chunks() {
+        assert!(matches!(
+            create_cancel_requests(&keypair(), "ETH-PERP", &[], None, MAX_CANCEL_ORDER_HASHES),
+            Err(SigningError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            create_cancel_requests(&keypair(), "ETH-PERP", &["0x1"], None, 0),
+            Err(SigningError::InvalidInput(_))
+        ));
+    }
+}
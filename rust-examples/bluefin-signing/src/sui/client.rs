@@ -0,0 +1,376 @@
+use super::address::SuiAddress;
+use super::api::{
+    AuthorizeRequest, CancelOrdersResponse, MarketMeta, OrderCancellationJSONRequest, OrderJSONRequest, OrderResponse,
+};
+use super::order::Order;
+use super::signature::BluefinSuiSignature;
+use crate::transport::{HttpMethod, ReqwestTransport, RestClient, RetryPolicy, Transport};
+use crate::{Environment, Result};
+
+/**
+ * Client of the Bluefin Sui REST API of an environment.
+ * The transport is shared by every request, so with `ReqwestTransport` connections are pooled;
+ * pass a `MockTransport` to run against an in process stand-in of the exchange.
+ * Idempotent calls (authorize, market metadata, cancels) are retried by the retry policy,
+ * placing an order never is: see `post_order_with_retry`
+ */
+#[derive(Debug, Clone)]
+pub struct BluefinClient<T: Transport = ReqwestTransport> {
+    rest: RestClient<T>,
+}
+
+impl BluefinClient {
+    pub fn new(environment: Environment) -> Self {
+        BluefinClient::with_transport(environment, ReqwestTransport::default())
+    }
+}
+
+impl<T: Transport> BluefinClient<T> {
+    pub fn with_transport(environment: Environment, transport: T) -> Self {
+        BluefinClient {
+            rest: RestClient::with_transport(environment, transport),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.rest = self.rest.with_retry_policy(retry_policy);
+        self
+    }
+
+    pub fn environment(&self) -> &Environment {
+        self.rest.environment()
+    }
+
+    pub fn transport(&self) -> &T {
+        self.rest.transport()
+    }
+
+    /**
+     * POSTS the onboarding signature and returns the JWT token
+     */
+    pub async fn authorize(&self, onboarding_signature: &BluefinSuiSignature, user_address: &SuiAddress) -> Result<String> {
+        self.rest
+            .authorize(&AuthorizeRequest::new(onboarding_signature, user_address)?)
+            .await
+    }
+
+    /**
+     * POSTS the signed order once, see `RestClient::post_order`
+     */
+    pub async fn post_order(&self, order_request: &OrderJSONRequest, jwt_token: &str) -> Result<OrderResponse> {
+        self.rest.post_order(order_request, jwt_token).await
+    }
+
+    /**
+     * POSTS the signed order with retries, see `RestClient::post_order_with_retry`
+     */
+    pub async fn post_order_with_retry(&self, order_request: &OrderJSONRequest, jwt_token: &str) -> Result<OrderResponse> {
+        self.rest.post_order_with_retry(order_request, jwt_token).await
+    }
+
+    /**
+     * POSTS the Order and returns the order hash computed by the exchange
+     */
+    pub async fn post_signed_order(&self, order: &Order, order_signature: &BluefinSuiSignature, jwt_token: &str) -> Result<String> {
+        let order_request = OrderJSONRequest::new(order, order_signature)?;
+        Ok(self.post_order(&order_request, jwt_token).await?.hash)
+    }
+
+    /**
+     * Sends the Cancellation Order
+     */
+    pub async fn cancel_orders(
+        &self,
+        order_cancel: &OrderCancellationJSONRequest,
+        jwt_token: &str,
+    ) -> Result<CancelOrdersResponse> {
+        self.rest.cancel_orders(order_cancel, jwt_token).await
+    }
+
+    /**
+     * Sends the requests made by `create_cancel_requests`, see `RestClient::cancel_orders_in_chunks`
+     */
+    pub async fn cancel_orders_in_chunks(
+        &self,
+        order_cancels: &[OrderCancellationJSONRequest],
+        jwt_token: &str,
+    ) -> Result<Vec<CancelOrdersResponse>> {
+        self.rest.cancel_orders_in_chunks(order_cancels, jwt_token).await
+    }
+
+    /**
+     * Given a market ("ETH-PERP" or "BTC-PERP"), returns its metadata
+     */
+    pub async fn get_market_meta(&self, market: &str) -> Result<MarketMeta> {
+        let request = self.rest.request(HttpMethod::Get, "/meta").query("symbol", market);
+        self.rest.send_idempotent(request).await
+    }
+
+    /**
+     * Given a market ("ETH-PERP" or "BTC-PERP"), returns the perpetual address
+     */
+    pub async fn get_market_id(&self, market: &str) -> Result<SuiAddress> {
+        self.get_market_meta(market).await?.perpetual_address.id.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::sui::{OrderType, Side, SignatureScheme, SuiKeyPair, TimeInForce};
+    use crate::transport::{HttpRequest, HttpResponse, MockTransport};
+    use crate::SigningError;
+
+    const WALLET_KEY: &str = "c501312ca9eb1aaac6344edbe160e41d3d8d79570e6440f2a84f7d9abf462270";
+
+    const API_URL: &str = "https://dapi.api.sui-staging.bluefin.io";
+
+    /**
+     * Client whose exchange answers with the given responses in turn, repeating the last one
+     */
+    fn client(responses: Vec<HttpResponse>) -> BluefinClient<MockTransport> {
+        let sent = AtomicUsize::new(0);
+        let transport = MockTransport::new(move |_| {
+            let index = sent.fetch_add(1, Ordering::SeqCst).min(responses.len() - 1);
+            responses[index].clone()
+        });
+        BluefinClient::with_transport(Environment::sui_staging(), transport).with_retry_policy(RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        })
+    }
+
+    fn order_request() -> OrderJSONRequest {
+        serde_json::from_value(json!({
+            "orderbookOnly": true,
+            "symbol": "ETH-PERP",
+            "price": "1800000000000000000000",
+            "quantity": "10000000000000000",
+            "triggerPrice": "0",
+            "leverage": "3000000000000000000",
+            "userAddress": "0xc6c71c996d437eb6589d1b8b17afcd1480afd5f30f6b7155ef468a9713d3240e",
+            "orderType": "LIMIT",
+            "side": "BUY",
+            "reduceOnly": false,
+            "salt": 1695466663327515u64,
+            "expiration": 1696489993397u64,
+            "orderSignature": "signature",
+            "timeInForce": "GTT",
+            "postOnly": false,
+            "cancelOnRevert": false,
+            "clientId": "bluefin-signing",
+        }))
+        .unwrap()
+    }
+
+    fn order_cancel() -> OrderCancellationJSONRequest {
+        serde_json::from_value(json!({
+            "symbol": "ETH-PERP",
+            "orderHashes": ["0x1"],
+            "parentAddress": "",
+            "cancelSignature": "signature",
+        }))
+        .unwrap()
+    }
+
+    fn ok(body: &str) -> HttpResponse {
+        HttpResponse::new(200, body)
+    }
+
+    #[tokio::test]
+    async fn post_order_is_sent_once_on_server_errors() {
+        let client = client(vec![HttpResponse::new(503, "unavailable"), ok(r#"{"hash": "0x1"}"#)]);
+
+        assert!(matches!(
+            client.post_order(&order_request(), "jwt").await,
+            Err(SigningError::Exchange { status: 503, .. })
+        ));
+        assert_eq!(client.transport().requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn post_order_with_retry_resends_the_identical_request() {
+        let client = client(vec![HttpResponse::new(503, "unavailable"), ok(r#"{"hash": "0x1"}"#)]);
+
+        assert_eq!(client.post_order_with_retry(&order_request(), "jwt").await.unwrap().hash, "0x1");
+        let requests = client.transport().requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0], requests[1]);
+    }
+
+    #[tokio::test]
+    async fn cancel_orders_retries_server_errors() {
+        let client = client(vec![
+            HttpResponse::new(503, "unavailable"),
+            HttpResponse::new(429, "rate limited"),
+            ok(r#"{"message": "ok"}"#),
+        ]);
+
+        assert!(client.cancel_orders(&order_cancel(), "jwt").await.is_ok());
+        assert_eq!(client.transport().requests().len(), 3);
+    }
+
+    /**
+     * Client whose exchange always gives the same answer
+     */
+    fn answering(status: u16, body: &str) -> BluefinClient<MockTransport> {
+        client(vec![HttpResponse::new(status, body)])
+    }
+
+    fn keypair() -> SuiKeyPair {
+        SuiKeyPair::from_hex(SignatureScheme::Ed25519, WALLET_KEY).unwrap()
+    }
+
+    fn header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+        request
+            .headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn body(request: &HttpRequest) -> Value {
+        serde_json::from_str(request.body.as_deref().unwrap()).unwrap()
+    }
+
+    fn order() -> Order {
+        Order {
+            market: "ETH-PERP".to_string(),
+            price: 1_800_000_000_000_000_000_000,
+            trigger_price: 0,
+            side: Side::Sell,
+            reduce_only: true,
+            quantity: 10_000_000_000_000_000,
+            post_only: false,
+            orderbook_only: true,
+            leverage: 3_000_000_000_000_000_000,
+            expiration: 1696489993397,
+            salt: 1695466663327515,
+            maker: keypair().address(),
+            ioc: false,
+            order_type: OrderType::Limit,
+            time_in_force: TimeInForce::Gtt,
+        }
+    }
+
+    #[tokio::test]
+    async fn authorize_posts_the_onboarding_signature() {
+        let client = answering(200, r#"{"token": "jwt"}"#);
+        let signature = keypair().sign(b"onboarding");
+
+        assert_eq!(client.authorize(&signature, &keypair().address()).await.unwrap(), "jwt");
+
+        let requests = client.transport().requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, HttpMethod::Post);
+        assert_eq!(requests[0].url, format!("{}/authorize", API_URL));
+        assert_eq!(header(&requests[0], "Authorization"), None);
+        assert_eq!(
+            body(&requests[0]),
+            json!({
+                "signature": signature.to_wire_format().unwrap(),
+                "userAddress": keypair().address().to_hex(),
+                "isTermAccepted": "True",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn post_signed_order_sends_the_order_with_the_token() {
+        let client = answering(200, r#"{"hash": "0x1234"}"#);
+        let signature = keypair().sign(b"order");
+
+        assert_eq!(client.post_signed_order(&order(), &signature, "jwt").await.unwrap(), "0x1234");
+
+        let requests = client.transport().requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, HttpMethod::Post);
+        assert_eq!(requests[0].url, format!("{}/orders", API_URL));
+        assert_eq!(header(&requests[0], "Authorization"), Some("Bearer jwt"));
+        let body = body(&requests[0]);
+        assert_eq!(body["symbol"], "ETH-PERP");
+        assert_eq!(body["side"], "SELL");
+        assert_eq!(body["reduceOnly"], true);
+        assert_eq!(body["price"], "1800000000000000000000");
+        assert_eq!(body["salt"], 1695466663327515u64);
+        assert_eq!(body["userAddress"], keypair().address().to_string());
+        assert_eq!(body["orderSignature"], signature.to_wire_format().unwrap());
+    }
+
+    #[tokio::test]
+    async fn cancel_orders_deletes_the_hashes_with_the_token() {
+        let client = answering(200, r#"{"message": "ok", "data": {"acceptedForCancelling": ["0x1"]}}"#);
+        let signature = keypair().sign(b"cancel");
+        let parent = "0x3".parse::<SuiAddress>().unwrap();
+        let order_cancel = OrderCancellationJSONRequest::new("ETH-PERP", &["0x1"], &signature, Some(&parent)).unwrap();
+
+        let response = client.cancel_orders(&order_cancel, "jwt").await.unwrap();
+        assert_eq!(response.message.as_deref(), Some("ok"));
+        assert_eq!(response.data["acceptedForCancelling"][0], "0x1");
+
+        let requests = client.transport().requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, HttpMethod::Delete);
+        assert_eq!(requests[0].url, format!("{}/orders/hash", API_URL));
+        assert_eq!(header(&requests[0], "Authorization"), Some("Bearer jwt"));
+        assert_eq!(
+            body(&requests[0]),
+            json!({
+                "symbol": "ETH-PERP",
+                "orderHashes": ["0x1"],
+                "parentAddress": parent.to_hex(),
+                "cancelSignature": signature.to_wire_format().unwrap(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn get_market_id_reads_the_perpetual_address() {
+        let client = answering(200, r#"{"perpetualAddress": {"id": "0x3"}, "symbol": "ETH-PERP"}"#);
+
+        assert_eq!(client.get_market_id("ETH-PERP").await.unwrap(), "0x3".parse::<SuiAddress>().unwrap());
+
+        let requests = client.transport().requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, HttpMethod::Get);
+        assert_eq!(requests[0].url, format!("{}/meta", API_URL));
+        assert_eq!(requests[0].query, vec![("symbol".to_string(), "ETH-PERP".to_string())]);
+        assert_eq!(requests[0].body, None);
+    }
+
+    #[tokio::test]
+    async fn decodes_both_exchange_error_bodies() {
+        for body in [
+            r#"{"error": {"code": 1004, "message": "invalid signature"}}"#,
+            r#"{"code": 1004, "message": "invalid signature"}"#,
+        ] {
+            match answering(400, body).get_market_meta("ETH-PERP").await {
+                Err(SigningError::Exchange { status, code, message }) => {
+                    assert_eq!((status, code, message.as_str()), (400, Some(1004), "invalid signature"))
+                }
+                other => panic!("expected an exchange error for {}, got {:?}", body, other),
+            }
+        }
+
+        match answering(401, "unauthorized").get_market_meta("ETH-PERP").await {
+            Err(SigningError::Exchange { status, code, message }) => {
+                assert_eq!((status, code, message.as_str()), (401, None, "unauthorized"))
+            }
+            other => panic!("expected an exchange error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_unexpected_success_bodies() {
+        let client = answering(200, r#"{"unexpected": true}"#);
+        assert!(matches!(
+            client.authorize(&keypair().sign(b"onboarding"), &keypair().address()).await,
+            Err(SigningError::Exchange { status: 200, code: None, .. })
+        ));
+    }
+}
@@ -0,0 +1,76 @@
+/**
+ * Intent scope of a Sui personal message, followed by intent version 0 and app id 0 (Sui)
+ */
+const PERSONAL_MESSAGE_INTENT: [u8; 3] = [3, 0, 0];
+
+/**
+ * Converts decimal to BCS encoding, i.e. the ULEB128 length prefix of a BCS vector
+ */
+pub fn decimal_to_bcs(num: u64) -> Vec<u8> {
+    let mut bcs_bytes: Vec<u8> = Vec::new();
+    let mut temp_num = num;
+    loop {
+        let bcs_byte = (temp_num & 0x7F) as u8;
+        temp_num >>= 7;
+
+        if temp_num == 0 {
+            bcs_bytes.push(bcs_byte);
+            return bcs_bytes;
+        }
+        bcs_bytes.push(bcs_byte | 0x80);
+    }
+}
+
+/**
+ * Frames a message as a Sui personal message intent: the intent bytes followed by the
+ * message serialized as a BCS vector<u8>
+ */
+pub fn get_personal_message_intent(message: &[u8]) -> Vec<u8> {
+    let mut intent = PERSONAL_MESSAGE_INTENT.to_vec();
+    intent.extend_from_slice(&decimal_to_bcs(message.len() as u64));
+    intent.extend_from_slice(message);
+    intent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn encodes_lengths_as_uleb128() {
+        assert_eq!(decimal_to_bcs(0), vec![0x00]);
+        assert_eq!(decimal_to_bcs(127), vec![0x7f]);
+        assert_eq!(decimal_to_bcs(128), vec![0x80, 0x01]);
+        assert_eq!(decimal_to_bcs(16383), vec![0xff, 0x7f]);
+        assert_eq!(decimal_to_bcs(16384), vec![0x80, 0x80, 0x01]);
+        assert_eq!(
+            decimal_to_bcs(u64::MAX),
+            vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]
+        );
+    }
+
+    #[test]
+    fn length_prefix_matches_bcs() {
+        for length in [0usize, 1, 127, 128, 300, 16383, 16384] {
+            let bytes = bcs::to_bytes(&vec![0u8; length]).unwrap();
+            assert_eq!(decimal_to_bcs(length as u64), bytes[..bytes.len() - length]);
+        }
+    }
+
+    #[test]
+    fn frames_messages_longer_than_127_bytes() {
+        let onboarding = json!({ "onboardingUrl": format!("https://{}.bluefin.io", "a".repeat(150)) })
+            .to_string()
+            .into_bytes();
+        let hashes: Vec<String> = (0..4).map(|i| format!("{:064x}", i)).collect();
+        let cancel = json!({ "orderHashes": hashes }).to_string().into_bytes();
+
+        for message in [onboarding, cancel] {
+            assert!(message.len() > 127);
+            let mut expected = PERSONAL_MESSAGE_INTENT.to_vec();
+            expected.extend(bcs::to_bytes(&message).unwrap());
+            assert_eq!(get_personal_message_intent(&message), expected);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use ed25519_dalek::Signer;
+
+use super::signature::{BluefinSuiSignature, SignatureScheme};
+use super::address::SuiAddress;
+use crate::{Result, SigningError};
+
+/**
+ * Private key of a Sui account, in any of the signature schemes Sui supports
+ */
+#[derive(Debug, Clone)]
+pub enum SuiKeyPair {
+    Ed25519(ed25519_dalek::SigningKey),
+    Secp256k1(k256::ecdsa::SigningKey),
+    Secp256r1(p256::ecdsa::SigningKey),
+}
+
+impl SuiKeyPair {
+    /**
+     * Loads a 32 byte private key of the given scheme
+     */
+    pub fn from_bytes(scheme: SignatureScheme, private_key: &[u8]) -> Result<Self> {
+        let key_error = |e: String| SigningError::Key(format!("invalid {:?} private key: {}", scheme, e));
+        let private_key_bytes: [u8; 32] = private_key
+            .try_into()
+            .map_err(|_| key_error(format!("must be 32 bytes, got {}", private_key.len())))?;
+
+        match scheme {
+            SignatureScheme::Ed25519 => Ok(SuiKeyPair::Ed25519(ed25519_dalek::SigningKey::from_bytes(
+                &private_key_bytes,
+            ))),
+            SignatureScheme::Secp256k1 => k256::ecdsa::SigningKey::from_bytes(&private_key_bytes.into())
+                .map(SuiKeyPair::Secp256k1)
+                .map_err(|e| key_error(e.to_string())),
+            SignatureScheme::Secp256r1 => p256::ecdsa::SigningKey::from_bytes(&private_key_bytes.into())
+                .map(SuiKeyPair::Secp256r1)
+                .map_err(|e| key_error(e.to_string())),
+        }
+    }
+
+    /**
+     * Loads a hex encoded private key of the given scheme
+     */
+    pub fn from_hex(scheme: SignatureScheme, wallet_key: &str) -> Result<Self> {
+        let bytes = hex::decode(wallet_key.trim_start_matches("0x"))
+            .map_err(|e| SigningError::Key(format!("private key is not valid hex: {}", e)))?;
+        SuiKeyPair::from_bytes(scheme, &bytes)
+    }
+
+    pub fn scheme(&self) -> SignatureScheme {
+        match self {
+            SuiKeyPair::Ed25519(_) => SignatureScheme::Ed25519,
+            SuiKeyPair::Secp256k1(_) => SignatureScheme::Secp256k1,
+            SuiKeyPair::Secp256r1(_) => SignatureScheme::Secp256r1,
+        }
+    }
+
+    /**
+     * Returns the public key, compressed for secp256k1 and secp256r1
+     */
+    pub fn public_key(&self) -> Vec<u8> {
+        match self {
+            SuiKeyPair::Ed25519(key) => key.verifying_key().to_bytes().to_vec(),
+            SuiKeyPair::Secp256k1(key) => key.verifying_key().to_encoded_point(true).as_bytes().to_vec(),
+            SuiKeyPair::Secp256r1(key) => key.verifying_key().to_encoded_point(true).as_bytes().to_vec(),
+        }
+    }
+
+    /**
+     * Returns the Sui address of the account
+     */
+    pub fn address(&self) -> SuiAddress {
+        SuiAddress::from_public_key(self.scheme(), &self.public_key())
+            .expect("keypair public keys match their scheme")
+    }
+
+    /**
+     * Signs a message the way Sui keypairs do: Ed25519 signs the message itself,
+     * secp256k1 and secp256r1 sign its SHA-256 with a low-s normalized signature
+     */
+    pub fn sign(&self, message: &[u8]) -> BluefinSuiSignature {
+        let signature = match self {
+            SuiKeyPair::Ed25519(key) => key.sign(message).to_bytes().to_vec(),
+            SuiKeyPair::Secp256k1(key) => {
+                let signature: k256::ecdsa::Signature = key.sign(message);
+                signature.normalize_s().unwrap_or(signature).to_bytes().to_vec()
+            }
+            SuiKeyPair::Secp256r1(key) => {
+                let signature: p256::ecdsa::Signature = key.sign(message);
+                signature.normalize_s().unwrap_or(signature).to_bytes().to_vec()
+            }
+        };
+
+        BluefinSuiSignature::new(self.scheme(), signature, self.public_key())
+            .expect("keypair produces signatures of its own scheme")
+    }
+}
+
+impl From<ed25519_dalek::SigningKey> for SuiKeyPair {
+    fn from(key: ed25519_dalek::SigningKey) -> Self {
+        SuiKeyPair::Ed25519(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use base64::Engine;
+
+    use super::*;
+
+    /**
+     * Keys exported by the Sui keytool as base64(flag || private key), with the addresses Sui derives for them
+     */
+    const SUI_KEYS: [(&str, &str); 4] = [
+        (
+            "AQA9EYZoLXirIahsXHQMDfdi5DPQ72wLA79zke4EY6CP",
+            "0x9e8f732575cc5386f8df3c784cd3ed1b53ce538da79926b2ad54dcc1197d2532",
+        ),
+        (
+            "Ae+TTptXI6WaJfzplSrphnrbTD5qgftfMX5kTyca7unQ",
+            "0x9fd5a804ed6b46d36949ff7434247f0fd594673973ece24aede6b86a7b5dae01",
+        ),
+        (
+            "AY2iJpGSDMhvGILPjjpyeM1bV4Jky979nUenB5kvQeSj",
+            "0x60287d7c38dee783c2ab1077216124011774be6b0764d62bd05f32c88979d5c5",
+        ),
+        (
+            "AiWmZXUcFpUF75H082F2RVJAABS5kcrvb8o09IPH9yUw",
+            "0x4a822457f1970468d38dae8e63fb60eefdaa497d74d781f581ea2d137ec36f3a",
+        ),
+    ];
+
+    #[test]
+    fn derives_the_sui_addresses_of_secp_keys() {
+        for (exported_key, address) in SUI_KEYS {
+            let bytes = BASE64.decode(exported_key).unwrap();
+            let keypair = SuiKeyPair::from_bytes(SignatureScheme::from_flag(bytes[0]).unwrap(), &bytes[1..]).unwrap();
+            assert_eq!(keypair.address().to_string(), address, "{:?}", keypair.scheme());
+        }
+    }
+
+    #[test]
+    fn derives_the_address_of_the_example_ed25519_key() {
+        let keypair = SuiKeyPair::from_hex(
+            SignatureScheme::Ed25519,
+            "c501312ca9eb1aaac6344edbe160e41d3d8d79570e6440f2a84f7d9abf462270",
+        )
+        .unwrap();
+        assert_eq!(
+            keypair.address().to_string(),
+            "0xc6c71c996d437eb6589d1b8b17afcd1480afd5f30f6b7155ef468a9713d3240e"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::address::SuiAddress;
+use super::client::BluefinClient;
+use crate::transport::Transport;
+use crate::{Result, SigningError};
+
+/**
+ * Perpetual object ids of the markets, keyed by symbol ("ETH-PERP", "BTC-PERP").
+ * Loaded once from a JSON file such as `{"ETH-PERP": "0x..."}` or filled from the API
+ * with `refresh`, so orders can be serialized without a network call
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MarketRegistry {
+    markets: BTreeMap<String, SuiAddress>,
+}
+
+impl MarketRegistry {
+    pub fn new() -> Self {
+        MarketRegistry::default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /**
+     * Loads the registry from a JSON config file
+     */
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .map_err(|e| SigningError::InvalidInput(format!("can not read {}: {}", path.display(), e)))?;
+        MarketRegistry::from_json(&json)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        fs::write(path, self.to_json()?)
+            .map_err(|e| SigningError::InvalidInput(format!("can not write {}: {}", path.display(), e)))
+    }
+
+    pub fn insert(&mut self, market: &str, market_id: SuiAddress) {
+        self.markets.insert(market.to_string(), market_id);
+    }
+
+    pub fn markets(&self) -> impl Iterator<Item = (&str, &SuiAddress)> {
+        self.markets.iter().map(|(market, id)| (market.as_str(), id))
+    }
+
+    /**
+     * Returns the perpetual id of a market, without touching the network
+     */
+    pub fn get_market_id(&self, market: &str) -> Result<SuiAddress> {
+        self.markets.get(market).copied().ok_or_else(|| {
+            SigningError::InvalidInput(format!("market {} is not in the registry, refresh it first", market))
+        })
+    }
+
+    /**
+     * Fetches the perpetual id of a market from the exchange and stores it in the registry
+     */
+    pub async fn refresh<T: Transport>(&mut self, client: &BluefinClient<T>, market: &str) -> Result<SuiAddress> {
+        let market_id = client.get_market_id(market).await?;
+        self.insert(market, market_id);
+        Ok(market_id)
+    }
+}
@@ -0,0 +1,22 @@
+//! Bluefin on Sui: order serialization, Ed25519/secp256k1/secp256r1 signing, onboarding and the REST API.
+
+pub mod address;
+pub mod api;
+pub mod client;
+pub mod intent;
+pub mod keypair;
+pub mod market;
+pub mod onboarding;
+pub mod order;
+pub mod personal_message;
+pub mod session;
+pub mod signature;
+
+pub use address::SuiAddress;
+pub use client::BluefinClient;
+pub use keypair::SuiKeyPair;
+pub use market::MarketRegistry;
+pub use order::{Order, OrderType, Side, TimeInForce};
+pub use personal_message::SuiPersonalMessageSigner;
+pub use session::{AuthSession, AuthToken};
+pub use signature::{verify_sui_cancel_signature, verify_sui_order_signature, BluefinSuiSignature, SignatureScheme};
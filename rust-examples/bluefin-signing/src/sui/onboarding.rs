@@ -0,0 +1,14 @@
+use serde_json::json;
+
+use super::keypair::SuiKeyPair;
+use super::personal_message::SuiPersonalMessageSigner;
+use super::signature::BluefinSuiSignature;
+use crate::Result;
+
+/**
+ * Signs the onboarding url (`Environment::onboarding_url`), the returned signature is sent to the /authorize route
+ */
+pub fn create_onboarding_signature(keypair: &SuiKeyPair, onboarding_url: &str) -> Result<BluefinSuiSignature> {
+    let signer = SuiPersonalMessageSigner::new(keypair.clone());
+    signer.sign_json(&json!({ "onboardingUrl": onboarding_url }))
+}
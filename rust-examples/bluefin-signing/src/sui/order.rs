@@ -0,0 +1,450 @@
+use serde_json::json;
+use sha256::digest;
+
+use super::address::SuiAddress;
+use super::keypair::SuiKeyPair;
+use super::market::MarketRegistry;
+use super::personal_message::SuiPersonalMessageSigner;
+use super::signature::BluefinSuiSignature;
+use crate::{OrderKind, Result, SigningError};
+
+pub use crate::order_type::{OrderType, Side, TimeInForce};
+
+/**
+ * An order on Bluefin Sui. The trigger price of stop orders is not part of the serialized order,
+ * the on-chain order has no such field: the exchange holds stop orders off-chain and only submits
+ * the signed order once the trigger price is reached, so the trigger price is sent in the REST payload alone
+ */
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub market: String,
+    pub price: u128,
+    pub trigger_price: u128,
+    pub side: Side,
+    pub reduce_only: bool,
+    pub quantity: u128,
+    pub post_only: bool,
+    pub orderbook_only: bool,
+    pub leverage: u128,
+    pub expiration: u128,
+    pub salt: u128,
+    pub maker: SuiAddress,
+    pub ioc: bool,
+    pub order_type: OrderType,
+    pub time_in_force: TimeInForce,
+}
+
+/**
+ * Tag closing every serialized order
+ */
+const BLUEFIN_TAG: &[u8] = b"Bluefin";
+
+/**
+ * Length of a serialized order: price, quantity, leverage and salt (16 bytes each), expiration (8),
+ * maker and market id (32 each), flags (1) and the Bluefin tag
+ */
+pub const SERIALIZED_ORDER_LENGTH: usize = 16 * 4 + 8 + 32 * 2 + 1 + BLUEFIN_TAG.len();
+
+/**
+ * Boolean fields of an order packed into the serialized flags byte
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderFlags {
+    pub ioc: bool,
+    pub post_only: bool,
+    pub reduce_only: bool,
+    pub is_buy: bool,
+    pub orderbook_only: bool,
+}
+
+/**
+ * Decodes the flags byte produced by `get_order_flags`
+ */
+pub fn decode_order_flags(flags: u8) -> Result<OrderFlags> {
+    if flags & !0x1F != 0 {
+        return Err(SigningError::InvalidInput(format!("unknown order flags {:#04x}", flags)));
+    }
+    Ok(OrderFlags {
+        ioc: flags & 1 != 0,
+        post_only: flags & 2 != 0,
+        reduce_only: flags & 4 != 0,
+        is_buy: flags & 8 != 0,
+        orderbook_only: flags & 16 != 0,
+    })
+}
+
+/**
+ * Encodes order flags and returns a 16 bit hex
+ */
+pub fn get_order_flags(order: &Order) -> u32 {
+    let mut flag = 0;
+
+    if order.ioc {
+        flag += 1;
+    };
+    if order.post_only {
+        flag += 2;
+    }
+    if order.reduce_only {
+        flag += 4;
+    }
+    if order.side == Side::Buy {
+        flag += 8
+    }
+    if order.orderbook_only {
+        flag += 16
+    }
+
+    flag
+}
+
+/**
+ * Rejects orders whose type, time in force and flags contradict each other
+ */
+pub fn validate_order(order: &Order) -> Result<()> {
+    if order.ioc != (order.time_in_force == TimeInForce::Ioc) {
+        return Err(SigningError::InvalidInput(format!(
+            "invalid order: ioc is {} but time in force is {}",
+            order.ioc, order.time_in_force
+        )));
+    }
+    OrderKind {
+        order_type: order.order_type,
+        time_in_force: order.time_in_force,
+        post_only: order.post_only,
+        has_price: order.price != 0,
+        has_trigger_price: order.trigger_price != 0,
+    }
+    .validate()
+}
+
+/**
+ * Given order hashes, returns the personal message that cancels them
+ */
+pub(crate) fn get_cancel_message(order_hashes: &[&str]) -> Vec<u8> {
+    json!({ "orderHashes": order_hashes }).to_string().into_bytes()
+}
+
+/**
+ * Given an order hash, returns a cancel order hash
+ */
+pub fn create_signed_cancel_order(order_hash: &str) -> [u8; 32] {
+    create_signed_cancel_orders(vec![order_hash])
+}
+
+/**
+ * Given an Vec of order hashes, returns a cancel order hash
+ */
+pub fn create_signed_cancel_orders(order_hashes: Vec<&str>) -> [u8; 32] {
+    SuiPersonalMessageSigner::hash_message(&get_cancel_message(&order_hashes))
+}
+
+/**
+ * Given an order, returns its serialized hex buffer.
+ * The market's perpetual id comes from the registry, so no network call is made.
+ * Fails if the order does not pass `validate_order`
+ */
+pub fn get_serialized_order(order: &Order, markets: &MarketRegistry) -> Result<String> {
+    validate_order(order)?;
+
+    let flags = get_order_flags(order);
+    let flags_array = format!("{:0>2x}", flags);
+
+    let order_price_hex = format!("{:0>32x}", order.price);
+    let order_quantity_hex = format!("{:0>32x}", order.quantity);
+    let order_leverage_hex = format!("{:0>32x}", order.leverage);
+    let order_salt = format!("{:0>32x}", order.salt);
+    let expiration = u64::try_from(order.expiration)
+        .map_err(|_| SigningError::Overflow("expiration does not fit in u64".to_string()))?;
+    let order_expiration = format!("{:0>16x}", expiration);
+    let order_maker = hex::encode(order.maker.as_bytes());
+    let market_id = markets.get_market_id(&order.market)?;
+    let order_market = hex::encode(market_id.as_bytes());
+    let bluefin_string = hex::encode("Bluefin");
+
+    Ok(order_price_hex
+        + &order_quantity_hex
+        + &order_leverage_hex
+        + &order_salt
+        + &order_expiration
+        + &order_maker
+        + &order_market
+        + &flags_array
+        + &bluefin_string)
+}
+
+/**
+ * Reads the next `N` bytes of a serialized order
+ */
+fn take<const N: usize>(bytes: &mut &[u8]) -> [u8; N] {
+    let (head, tail) = bytes.split_at(N);
+    *bytes = tail;
+    head.try_into().expect("split_at returns N bytes")
+}
+
+/**
+ * Decodes a serialized order buffer back into the order that was signed.
+ * The market is reported by symbol when its perpetual id is in the registry, otherwise as the id itself.
+ * Order type, trigger price and time in force are not part of the buffer: price 0 is read as a MARKET order,
+ * every order as having no trigger price, and the ioc flag as IOC time in force
+ */
+pub fn deserialize_order_bytes(serialized_order: &[u8], markets: &MarketRegistry) -> Result<Order> {
+    if serialized_order.len() != SERIALIZED_ORDER_LENGTH {
+        return Err(SigningError::InvalidInput(format!(
+            "serialized order must be {} bytes, got {}",
+            SERIALIZED_ORDER_LENGTH,
+            serialized_order.len()
+        )));
+    }
+    if !serialized_order.ends_with(BLUEFIN_TAG) {
+        return Err(SigningError::InvalidInput("serialized order does not end with the Bluefin tag".to_string()));
+    }
+
+    let mut bytes = serialized_order;
+    let price = u128::from_be_bytes(take(&mut bytes));
+    let quantity = u128::from_be_bytes(take(&mut bytes));
+    let leverage = u128::from_be_bytes(take(&mut bytes));
+    let salt = u128::from_be_bytes(take(&mut bytes));
+    let expiration = u64::from_be_bytes(take(&mut bytes)) as u128;
+    let maker = SuiAddress::new(take(&mut bytes));
+    let market_id = SuiAddress::new(take(&mut bytes));
+    let flags = decode_order_flags(take::<1>(&mut bytes)[0])?;
+
+    let market = markets
+        .markets()
+        .find(|(_, id)| **id == market_id)
+        .map(|(market, _)| market.to_string())
+        .unwrap_or_else(|| market_id.to_hex());
+
+    Ok(Order {
+        market,
+        price,
+        trigger_price: 0,
+        side: if flags.is_buy { Side::Buy } else { Side::Sell },
+        reduce_only: flags.reduce_only,
+        quantity,
+        post_only: flags.post_only,
+        orderbook_only: flags.orderbook_only,
+        leverage,
+        expiration,
+        salt,
+        maker,
+        ioc: flags.ioc,
+        order_type: if price == 0 { OrderType::Market } else { OrderType::Limit },
+        time_in_force: if flags.ioc { TimeInForce::Ioc } else { TimeInForce::Gtt },
+    })
+}
+
+/**
+ * Decodes a hex serialized order, as returned by `get_serialized_order`
+ */
+pub fn deserialize_order(serialized_order: &str, markets: &MarketRegistry) -> Result<Order> {
+    let bytes = hex::decode(serialized_order.trim_start_matches("0x"))?;
+    deserialize_order_bytes(&bytes, markets)
+}
+
+/**
+ * Given a serialized order, returns the order hash the exchange reports for it
+ */
+pub fn get_order_hash(serialized_order: &str) -> Result<String> {
+    Ok(digest(hex::decode(serialized_order.trim_start_matches("0x"))?))
+}
+
+/**
+ * Returns the digest that gets signed for a serialized order
+ */
+pub(crate) fn get_order_signing_digest(serialized_order: &str) -> Result<Vec<u8>> {
+    // the signed digest is the sha256 of the bare hex string itself, so reject anything that is not hex
+    let serialized_order = serialized_order.trim_start_matches("0x");
+    hex::decode(serialized_order)?;
+    Ok(hex::decode(digest(serialized_order))?)
+}
+
+/**
+ * Signs a serialized order
+ */
+pub fn sign_order(keypair: &SuiKeyPair, serialized_order: &str) -> Result<BluefinSuiSignature> {
+    let msg_hash_decoded = get_order_signing_digest(serialized_order)?;
+    Ok(keypair.sign(&msg_hash_decoded))
+}
+
+/**
+ * Signs the cancellation of the given order hashes
+ */
+pub fn sign_cancel_orders(keypair: &SuiKeyPair, order_hashes: Vec<&str>) -> BluefinSuiSignature {
+    let signer = SuiPersonalMessageSigner::new(keypair.clone());
+    signer.sign_bytes(&get_cancel_message(&order_hashes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sui::signature::SignatureScheme;
+
+    const WALLET_KEY: &str = "c501312ca9eb1aaac6344edbe160e41d3d8d79570e6440f2a84f7d9abf462270";
+
+    /**
+     * The serialized order of the sui-order-signing example, with ETH-PERP at id 0x3
+     */
+    const EXAMPLE_SERIALIZED_ORDER: &str = "000000000000000000000000000000000000000000000000002386f26fc10000000000000000000029a2241af62c00000000000000000000000606049832371b0000018afeaef4b5c6c71c996d437eb6589d1b8b17afcd1480afd5f30f6b7155ef468a9713d3240e000000000000000000000000000000000000000000000000000000000000000318426c756566696e";
+
+    fn markets() -> MarketRegistry {
+        let mut markets = MarketRegistry::new();
+        markets.insert("ETH-PERP", "0x3".parse().unwrap());
+        markets
+    }
+
+    fn example_order() -> Order {
+        Order {
+            market: "ETH-PERP".to_string(),
+            price: 0,
+            trigger_price: 0,
+            side: Side::Buy,
+            reduce_only: false,
+            quantity: 10_000_000_000_000_000,
+            post_only: false,
+            orderbook_only: true,
+            leverage: 3_000_000_000_000_000_000,
+            expiration: 1696489993397,
+            salt: 1695466663327515,
+            maker: SuiKeyPair::from_hex(SignatureScheme::Ed25519, WALLET_KEY).unwrap().address(),
+            ioc: false,
+            order_type: OrderType::Market,
+            time_in_force: TimeInForce::Gtt,
+        }
+    }
+
+    #[test]
+    fn serialized_order_matches_the_baseline() {
+        let serialized_order = get_serialized_order(&example_order(), &markets()).unwrap();
+        assert_eq!(serialized_order, EXAMPLE_SERIALIZED_ORDER);
+        assert_eq!(serialized_order.len(), SERIALIZED_ORDER_LENGTH * 2);
+        assert_eq!(
+            get_order_hash(&serialized_order).unwrap(),
+            "8f4350a82739c4c5d6528dae76df99da64fc68084248786376cd1dbcafddd91b"
+        );
+    }
+
+    #[test]
+    fn hashes_and_signs_serialized_orders_with_or_without_0x() {
+        let prefixed = format!("0x{}", EXAMPLE_SERIALIZED_ORDER);
+        assert_eq!(get_order_hash(&prefixed).unwrap(), get_order_hash(EXAMPLE_SERIALIZED_ORDER).unwrap());
+        assert_eq!(
+            get_order_signing_digest(&prefixed).unwrap(),
+            get_order_signing_digest(EXAMPLE_SERIALIZED_ORDER).unwrap()
+        );
+
+        let keypair = SuiKeyPair::from_hex(SignatureScheme::Ed25519, WALLET_KEY).unwrap();
+        assert_eq!(sign_order(&keypair, &prefixed).unwrap(), sign_order(&keypair, EXAMPLE_SERIALIZED_ORDER).unwrap());
+        assert!(get_order_hash("0xnot hex").is_err());
+        assert!(get_order_signing_digest("0xnot hex").is_err());
+    }
+
+    #[test]
+    fn orders_round_trip_through_serialization() {
+        for flags in 0u8..32 {
+            let mut order = example_order();
+            order.ioc = flags & 1 != 0;
+            order.post_only = flags & 2 != 0;
+            order.reduce_only = flags & 4 != 0;
+            order.side = if flags & 8 != 0 { Side::Buy } else { Side::Sell };
+            order.orderbook_only = flags & 16 != 0;
+            order.time_in_force = if order.ioc { TimeInForce::Ioc } else { TimeInForce::Gtt };
+            order.order_type = OrderType::Limit;
+            order.price = 1_800_000_000_000_000_000_000;
+            order.salt = u128::MAX;
+            if order.post_only && order.ioc {
+                assert!(get_serialized_order(&order, &markets()).is_err());
+                continue;
+            }
+
+            let serialized_order = get_serialized_order(&order, &markets()).unwrap();
+            assert_eq!(get_order_flags(&order), flags as u32);
+            let decoded = deserialize_order(&format!("0x{}", serialized_order), &markets()).unwrap();
+            assert_eq!(format!("{:?}", decoded), format!("{:?}", order));
+        }
+    }
+
+    #[test]
+    fn unknown_markets_decode_to_their_id() {
+        let decoded = deserialize_order(EXAMPLE_SERIALIZED_ORDER, &MarketRegistry::new()).unwrap();
+        assert_eq!(decoded.market, "0x0000000000000000000000000000000000000000000000000000000000000003");
+        assert_eq!(decoded.order_type, OrderType::Market);
+    }
+
+    #[test]
+    fn rejects_malformed_serialized_orders() {
+        let bytes = hex::decode(EXAMPLE_SERIALIZED_ORDER).unwrap();
+
+        assert!(deserialize_order_bytes(&bytes[..bytes.len() - 1], &markets()).is_err());
+        assert!(deserialize_order_bytes(&[bytes.as_slice(), &[0]].concat(), &markets()).is_err());
+        assert!(deserialize_order_bytes(&[], &markets()).is_err());
+
+        let mut wrong_tag = bytes.clone();
+        *wrong_tag.last_mut().unwrap() = b'N';
+        assert!(deserialize_order_bytes(&wrong_tag, &markets()).is_err());
+
+        let mut unknown_flags = bytes.clone();
+        unknown_flags[SERIALIZED_ORDER_LENGTH - BLUEFIN_TAG.len() - 1] = 0x20;
+        assert!(deserialize_order_bytes(&unknown_flags, &markets()).is_err());
+
+        assert!(deserialize_order("not hex", &markets()).is_err());
+    }
+
+    #[test]
+    fn validates_order_kinds() {
+        let mut limit = example_order();
+        limit.order_type = OrderType::Limit;
+        limit.price = 1_800_000_000_000_000_000_000;
+        assert!(validate_order(&limit).is_ok());
+
+        let invalid = |change: &dyn Fn(&mut Order)| {
+            let mut order = limit.clone();
+            change(&mut order);
+            matches!(validate_order(&order), Err(SigningError::InvalidInput(_)))
+        };
+        assert!(invalid(&|order| {
+            order.post_only = true;
+            order.ioc = true;
+            order.time_in_force = TimeInForce::Ioc;
+        }));
+        assert!(invalid(&|order| {
+            order.post_only = true;
+            order.time_in_force = TimeInForce::Fok;
+        }));
+        assert!(invalid(&|order| {
+            order.post_only = true;
+            order.order_type = OrderType::Market;
+        }));
+        assert!(invalid(&|order| order.price = 0));
+        assert!(invalid(&|order| order.order_type = OrderType::StopLimit));
+        assert!(invalid(&|order| {
+            order.order_type = OrderType::StopMarket;
+            order.price = 0;
+        }));
+        assert!(invalid(&|order| order.trigger_price = 1_700_000_000_000_000_000_000));
+        assert!(invalid(&|order| {
+            order.order_type = OrderType::Market;
+            order.trigger_price = 1_700_000_000_000_000_000_000;
+        }));
+        // the ioc flag that gets serialized must agree with the time in force
+        assert!(invalid(&|order| order.ioc = true));
+        assert!(invalid(&|order| order.time_in_force = TimeInForce::Ioc));
+        assert!(invalid(&|order| {
+            order.ioc = true;
+            order.time_in_force = TimeInForce::Fok;
+        }));
+        assert!(!invalid(&|order| {
+            order.ioc = true;
+            order.time_in_force = TimeInForce::Ioc;
+        }));
+        assert!(!invalid(&|order| order.post_only = true));
+    }
+
+    #[test]
+    fn serialization_needs_the_market_id() {
+        let mut order = example_order();
+        order.market = "BTC-PERP".to_string();
+        assert!(get_serialized_order(&order, &markets()).is_err());
+    }
+}
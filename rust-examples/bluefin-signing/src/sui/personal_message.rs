@@ -0,0 +1,85 @@
+use blake2b_simd::Params;
+use serde::Serialize;
+
+use super::address::SuiAddress;
+use super::intent::get_personal_message_intent;
+use super::keypair::SuiKeyPair;
+use super::signature::{BluefinSuiSignature, SignatureScheme};
+use crate::Result;
+
+/**
+ * Signs Sui personal messages: the message is framed as a PersonalMessage intent,
+ * hashed with Blake2b-256 and the digest is signed
+ */
+#[derive(Debug, Clone)]
+pub struct SuiPersonalMessageSigner {
+    keypair: SuiKeyPair,
+}
+
+impl SuiPersonalMessageSigner {
+    pub fn new(keypair: impl Into<SuiKeyPair>) -> Self {
+        SuiPersonalMessageSigner {
+            keypair: keypair.into(),
+        }
+    }
+
+    /**
+     * Creates a signer from a hex encoded private key of the given scheme
+     */
+    pub fn from_hex(scheme: SignatureScheme, wallet_key: &str) -> Result<Self> {
+        Ok(SuiPersonalMessageSigner::new(SuiKeyPair::from_hex(scheme, wallet_key)?))
+    }
+
+    pub fn keypair(&self) -> &SuiKeyPair {
+        &self.keypair
+    }
+
+    pub fn public_key(&self) -> Vec<u8> {
+        self.keypair.public_key()
+    }
+
+    pub fn address(&self) -> SuiAddress {
+        self.keypair.address()
+    }
+
+    /**
+     * Returns the Blake2b-256 hash of the personal message intent of the message
+     */
+    pub fn hash_message(message: &[u8]) -> [u8; 32] {
+        let intent = get_personal_message_intent(message);
+        let hash = Params::new()
+            .hash_length(32)
+            .to_state()
+            .update(&intent)
+            .finalize();
+
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(hash.as_bytes());
+        digest
+    }
+
+    /**
+     * Returns the JSON serialization of a payload, the bytes that get signed by `sign_json`
+     */
+    pub fn serialize_json<T: Serialize>(payload: &T) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(payload)?)
+    }
+
+    /**
+     * Returns true if the signature is a valid personal message signature of the message
+     */
+    pub fn verify_bytes(message: &[u8], signature: &BluefinSuiSignature) -> bool {
+        signature.verify(&SuiPersonalMessageSigner::hash_message(message))
+    }
+
+    pub fn sign_bytes(&self, message: &[u8]) -> BluefinSuiSignature {
+        self.keypair.sign(&SuiPersonalMessageSigner::hash_message(message))
+    }
+
+    /**
+     * Signs the JSON serialization of a payload, as done for onboarding and cancellation messages
+     */
+    pub fn sign_json<T: Serialize>(&self, payload: &T) -> Result<BluefinSuiSignature> {
+        Ok(self.sign_bytes(&SuiPersonalMessageSigner::serialize_json(payload)?))
+    }
+}
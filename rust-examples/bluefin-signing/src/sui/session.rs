@@ -0,0 +1,448 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::address::SuiAddress;
+use super::api::{CancelOrdersResponse, OrderCancellationJSONRequest};
+use super::client::BluefinClient;
+use super::keypair::SuiKeyPair;
+use super::onboarding::create_onboarding_signature;
+use super::order::Order;
+use super::signature::BluefinSuiSignature;
+use crate::transport::{ReqwestTransport, Transport};
+use crate::{Result, SigningError};
+
+/**
+ * Seconds before its expiry at which a token is already treated as expired,
+ * so a request is not sent with a token that runs out on the way
+ */
+const EXPIRY_LEEWAY_SECONDS: u64 = 60;
+
+/**
+ * A JWT token returned by /authorize, with the expiry read from its `exp` claim
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthToken {
+    pub token: String,
+    /// unix time in seconds, none if the token carries no `exp` claim
+    pub expires_at: Option<u64>,
+}
+
+impl AuthToken {
+    /**
+     * Reads the claims of a JWT token. The signature of the token is not checked, that is for the exchange to do
+     */
+    pub fn parse(token: &str) -> Result<Self> {
+        let invalid = |reason: &str| SigningError::InvalidInput(format!("malformed JWT token: {}", reason));
+
+        let mut parts = token.split('.');
+        let payload = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(_), Some(payload), Some(_), None) => payload,
+            _ => return Err(invalid("expected three dot separated parts")),
+        };
+        let payload = BASE64_URL
+            .decode(payload.trim_end_matches('='))
+            .map_err(|_| invalid("payload is not base64url"))?;
+        let claims: Value = serde_json::from_slice(&payload).map_err(|_| invalid("payload is not JSON"))?;
+
+        Ok(AuthToken {
+            token: token.to_string(),
+            expires_at: claims.get("exp").and_then(Value::as_u64),
+        })
+    }
+
+    /**
+     * Returns true if the token expires within the leeway of `now` (unix seconds)
+     */
+    pub fn is_expired_at(&self, now: u64) -> bool {
+        self.expires_at
+            .map(|expires_at| expires_at <= now + EXPIRY_LEEWAY_SECONDS)
+            .unwrap_or(false)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(unix_now())
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or(0)
+}
+
+/**
+ * Token file contents, the address tells whose token it is
+ */
+#[derive(Serialize, Deserialize)]
+struct StoredToken {
+    address: SuiAddress,
+    #[serde(flatten)]
+    token: AuthToken,
+}
+
+/**
+ * Keeps an account authorized: onboards on first use, when the token is about to expire,
+ * and when the exchange answers 401. The token can be kept in a file so restarts reuse it
+ */
+#[derive(Debug)]
+pub struct AuthSession<T: Transport = ReqwestTransport> {
+    client: BluefinClient<T>,
+    keypair: SuiKeyPair,
+    token_path: Option<PathBuf>,
+    token: Mutex<Option<AuthToken>>,
+}
+
+impl<T: Transport> AuthSession<T> {
+    pub fn new(client: BluefinClient<T>, keypair: SuiKeyPair) -> Self {
+        AuthSession {
+            client,
+            keypair,
+            token_path: None,
+            token: Mutex::new(None),
+        }
+    }
+
+    /**
+     * Persists the token to the file, and reuses the token already there if it belongs to this account.
+     * A file that can not be read or holds no valid token is ignored: the session onboards and overwrites it
+     */
+    pub fn with_token_file(mut self, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        if let Some(stored) = read_stored_token(path) {
+            if stored.address == self.keypair.address() {
+                *self.token.get_mut().expect("session lock poisoned") = Some(stored.token);
+            }
+        }
+        self.token_path = Some(path.to_path_buf());
+        self
+    }
+
+    pub fn client(&self) -> &BluefinClient<T> {
+        &self.client
+    }
+
+    pub fn address(&self) -> SuiAddress {
+        self.keypair.address()
+    }
+
+    /**
+     * Returns the current token, if onboarding happened already
+     */
+    pub fn current_token(&self) -> Option<AuthToken> {
+        self.token.lock().expect("session lock poisoned").clone()
+    }
+
+    /**
+     * Drops the current token so the next request onboards again
+     */
+    pub fn invalidate(&self) {
+        *self.token.lock().expect("session lock poisoned") = None;
+    }
+
+    /**
+     * Signs the onboarding url of the environment, exchanges it for a new token and stores it
+     */
+    pub async fn onboard(&self) -> Result<AuthToken> {
+        let onboarding_signature = create_onboarding_signature(&self.keypair, &self.client.environment().onboarding_url)?;
+        let token = AuthToken::parse(&self.client.authorize(&onboarding_signature, &self.address()).await?)?;
+        // kept in memory first, so a token file that can not be written does not lose the new token
+        *self.token.lock().expect("session lock poisoned") = Some(token.clone());
+        self.save(&token)?;
+        Ok(token)
+    }
+
+    fn save(&self, token: &AuthToken) -> Result<()> {
+        let path = match &self.token_path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let json = serde_json::to_string_pretty(&StoredToken {
+            address: self.address(),
+            token: token.clone(),
+        })?;
+        write_private(path, &json)
+            .map_err(|e| SigningError::InvalidInput(format!("can not write {}: {}", path.display(), e)))
+    }
+
+    /**
+     * Returns a token that is not expired, onboarding if there is none
+     */
+    pub async fn token(&self) -> Result<String> {
+        match self.current_token() {
+            Some(token) if !token.is_expired() => Ok(token.token),
+            _ => Ok(self.onboard().await?.token),
+        }
+    }
+
+    /**
+     * Runs an authorized request. If the exchange rejects the token with 401, onboards again and
+     * runs the request once more
+     */
+    pub async fn authorized<R, F, Fut>(&self, request: F) -> Result<R>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<R>>,
+    {
+        match request(self.token().await?).await {
+            Err(SigningError::Exchange { status: 401, .. }) => {
+                self.invalidate();
+                request(self.token().await?).await
+            }
+            result => result,
+        }
+    }
+
+    /**
+     * POSTS the Order and returns the order hash computed by the exchange
+     */
+    pub async fn post_signed_order(&self, order: &Order, order_signature: &BluefinSuiSignature) -> Result<String> {
+        self.authorized(|jwt_token| async move { self.client.post_signed_order(order, order_signature, &jwt_token).await })
+            .await
+    }
+
+    /**
+     * Sends the Cancellation Order
+     */
+    pub async fn cancel_orders(&self, order_cancel: &OrderCancellationJSONRequest) -> Result<CancelOrdersResponse> {
+        self.authorized(|jwt_token| async move { self.client.cancel_orders(order_cancel, &jwt_token).await })
+            .await
+    }
+}
+
+/**
+ * Reads the token file, none if it is missing, unreadable or not a token file (truncated by a crash for example)
+ */
+fn read_stored_token(path: &Path) -> Option<StoredToken> {
+    let json = fs::read_to_string(path).ok()?;
+    let stored: StoredToken = serde_json::from_str(&json).ok()?;
+    AuthToken::parse(&stored.token.token).ok()?;
+    Some(stored)
+}
+
+/**
+ * Writes a file only the owner can read, the token grants trading on the account.
+ * The mode given on open only applies to a new file, so the permissions of an existing one are set as well
+ */
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::sui::order::sign_cancel_orders;
+    use crate::sui::SignatureScheme;
+    use crate::transport::{HttpRequest, HttpResponse, MockTransport};
+    use crate::Environment;
+
+    const WALLET_KEY: &str = "c501312ca9eb1aaac6344edbe160e41d3d8d79570e6440f2a84f7d9abf462270";
+
+    /**
+     * Unsigned JWT token expiring at `exp`
+     */
+    fn jwt(exp: u64) -> String {
+        format!("e30.{}.c2ln", BASE64_URL.encode(format!(r#"{{"exp":{}}}"#, exp)))
+    }
+
+    fn session(token: &str) -> AuthSession<MockTransport> {
+        let body = format!(r#"{{"token": "{}"}}"#, token);
+        let transport = MockTransport::new(move |_| HttpResponse::new(200, body.clone()));
+        let keypair = SuiKeyPair::from_hex(SignatureScheme::Ed25519, WALLET_KEY).unwrap();
+        AuthSession::new(BluefinClient::with_transport(Environment::sui_staging(), transport), keypair)
+    }
+
+    fn keypair() -> SuiKeyPair {
+        SuiKeyPair::from_hex(SignatureScheme::Ed25519, WALLET_KEY).unwrap()
+    }
+
+    /**
+     * Session whose exchange hands out a new token `jwt-<n>` on every /authorize and answers
+     * the cancels with the given statuses in turn, repeating the last one
+     */
+    fn exchange(cancel_statuses: Vec<u16>) -> AuthSession<MockTransport> {
+        let authorized = AtomicUsize::new(0);
+        let cancelled = AtomicUsize::new(0);
+        let transport = MockTransport::new(move |request| {
+            if request.url.ends_with("/authorize") {
+                let n = authorized.fetch_add(1, Ordering::SeqCst) + 1;
+                return HttpResponse::new(200, format!(r#"{{"token": "{}"}}"#, jwt_numbered(n)));
+            }
+            let index = cancelled.fetch_add(1, Ordering::SeqCst).min(cancel_statuses.len() - 1);
+            match cancel_statuses[index] {
+                200 => HttpResponse::new(200, r#"{"message": "ok"}"#),
+                status => HttpResponse::new(status, "unauthorized"),
+            }
+        });
+        AuthSession::new(BluefinClient::with_transport(Environment::sui_staging(), transport), keypair())
+    }
+
+    /**
+     * Unexpired JWT token told apart from the others by its `n` claim
+     */
+    fn jwt_numbered(n: usize) -> String {
+        let claims = format!(r#"{{"exp":{},"n":{}}}"#, unix_now() + 3_600, n);
+        format!("e30.{}.c2ln", BASE64_URL.encode(claims))
+    }
+
+    fn order_cancel() -> OrderCancellationJSONRequest {
+        let signature = sign_cancel_orders(&keypair(), vec!["0x1"]);
+        OrderCancellationJSONRequest::new("ETH-PERP", &["0x1"], &signature, None).unwrap()
+    }
+
+    /**
+     * The route and bearer token of every request sent
+     */
+    fn sent(session: &AuthSession<MockTransport>) -> Vec<(String, Option<String>)> {
+        let bearer = |request: &HttpRequest| {
+            request
+                .headers
+                .iter()
+                .find(|(name, _)| name == "Authorization")
+                .map(|(_, value)| value.trim_start_matches("Bearer ").to_string())
+        };
+        session
+            .client()
+            .transport()
+            .requests()
+            .iter()
+            .map(|request| (request.url.rsplit('/').next().unwrap().to_string(), bearer(request)))
+            .collect()
+    }
+
+    fn token_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("bluefin-{}-{}.json", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn parses_the_expiry_of_tokens() {
+        let token = AuthToken::parse(&jwt(1_000)).unwrap();
+        assert_eq!(token.expires_at, Some(1_000));
+        assert!(!token.is_expired_at(1_000 - EXPIRY_LEEWAY_SECONDS - 1));
+        assert!(token.is_expired_at(1_000 - EXPIRY_LEEWAY_SECONDS));
+        assert!(AuthToken::parse("not a token").is_err());
+    }
+
+    #[tokio::test]
+    async fn reuses_a_stored_token_of_the_same_account() {
+        let path = token_file("reuse");
+        let token = jwt(unix_now() + 3_600);
+        session(&token).with_token_file(&path).onboard().await.unwrap();
+
+        let session = session(&jwt(1)).with_token_file(&path);
+        assert_eq!(session.token().await.unwrap(), token);
+        assert!(session.client().transport().requests().is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn ignores_corrupt_token_files() {
+        let path = token_file("corrupt");
+        let token = jwt(unix_now() + 3_600);
+        for contents in ["", "{\"address\": \"0x1", "{}", "not json"] {
+            fs::write(&path, contents).unwrap();
+            let session = session(&token).with_token_file(&path);
+            assert_eq!(session.current_token(), None);
+            assert_eq!(session.token().await.unwrap(), token);
+            assert_eq!(session.client().transport().requests().len(), 1);
+            assert_eq!(read_stored_token(&path).unwrap().token.token, token);
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn makes_existing_token_files_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = token_file("private");
+        fs::write(&path, "").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        session(&jwt(unix_now() + 3_600)).with_token_file(&path).onboard().await.unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn onboards_once_when_the_stored_token_expired() {
+        let path = token_file("expired");
+        session(&jwt(unix_now())).with_token_file(&path).onboard().await.unwrap();
+
+        let session = exchange(vec![200]).with_token_file(&path);
+        assert!(session.current_token().unwrap().is_expired());
+        assert!(session.cancel_orders(&order_cancel()).await.is_ok());
+        assert_eq!(
+            sent(&session),
+            vec![("authorize".to_string(), None), ("hash".to_string(), Some(jwt_numbered(1)))]
+        );
+        assert_eq!(read_stored_token(&path).unwrap().token.token, jwt_numbered(1));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn onboards_again_and_retries_once_on_401() {
+        let session = exchange(vec![401, 200]);
+
+        assert!(session.cancel_orders(&order_cancel()).await.is_ok());
+        assert_eq!(
+            sent(&session),
+            vec![
+                ("authorize".to_string(), None),
+                ("hash".to_string(), Some(jwt_numbered(1))),
+                ("authorize".to_string(), None),
+                ("hash".to_string(), Some(jwt_numbered(2))),
+            ]
+        );
+        assert_eq!(session.current_token().unwrap().token, jwt_numbered(2));
+    }
+
+    #[tokio::test]
+    async fn returns_a_second_401_without_onboarding_again() {
+        let session = exchange(vec![401]);
+
+        assert!(matches!(
+            session.cancel_orders(&order_cancel()).await,
+            Err(SigningError::Exchange { status: 401, .. })
+        ));
+        assert_eq!(sent(&session).len(), 4);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_token_when_the_token_file_can_not_be_written() {
+        let token = jwt(unix_now() + 3_600);
+        let session = session(&token).with_token_file(std::env::temp_dir());
+
+        assert!(session.onboard().await.is_err());
+        assert_eq!(session.current_token().unwrap().token, token);
+        assert!(session.token().await.is_ok());
+        assert_eq!(session.client().transport().requests().len(), 1);
+    }
+}
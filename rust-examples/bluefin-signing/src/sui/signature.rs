@@ -0,0 +1,427 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use std::fmt;
+use std::str::FromStr;
+
+use super::address::SuiAddress;
+use super::market::MarketRegistry;
+use super::order::{get_cancel_message, get_order_signing_digest, get_serialized_order, Order};
+use super::personal_message::SuiPersonalMessageSigner;
+use crate::{Result, SigningError};
+
+/**
+ * Signature schemes of Sui accounts
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureScheme {
+    Ed25519,
+    Secp256k1,
+    Secp256r1,
+}
+
+impl SignatureScheme {
+    /**
+     * Flag byte used by Sui in serialized signatures and in address derivation
+     */
+    pub fn flag(&self) -> u8 {
+        match self {
+            SignatureScheme::Ed25519 => 0x00,
+            SignatureScheme::Secp256k1 => 0x01,
+            SignatureScheme::Secp256r1 => 0x02,
+        }
+    }
+
+    pub fn from_flag(flag: u8) -> Result<Self> {
+        match flag {
+            0x00 => Ok(SignatureScheme::Ed25519),
+            0x01 => Ok(SignatureScheme::Secp256k1),
+            0x02 => Ok(SignatureScheme::Secp256r1),
+            _ => Err(SigningError::InvalidInput(format!("unknown signature scheme flag {}", flag))),
+        }
+    }
+
+    /**
+     * Character placed between the signature and the public key in the exchange wire format, the signer type
+     * the exchange verifies the signature with: `1` for Ed25519 and `2` for secp256k1 keys.
+     * The exchange has no signer type for secp256r1, so those signatures only have the Sui format
+     */
+    pub fn wire_suffix(&self) -> Result<char> {
+        match self {
+            SignatureScheme::Ed25519 => Ok('1'),
+            SignatureScheme::Secp256k1 => Ok('2'),
+            SignatureScheme::Secp256r1 => Err(SigningError::InvalidInput(format!(
+                "the exchange wire format has no signer type for {:?} signatures",
+                self
+            ))),
+        }
+    }
+
+    pub fn from_wire_suffix(suffix: char) -> Result<Self> {
+        match suffix {
+            '1' => Ok(SignatureScheme::Ed25519),
+            '2' => Ok(SignatureScheme::Secp256k1),
+            _ => Err(SigningError::InvalidInput(format!("unknown signature scheme suffix {}", suffix))),
+        }
+    }
+
+    /**
+     * Length of the public key, secp256k1 and secp256r1 keys are compressed points
+     */
+    pub fn public_key_length(&self) -> usize {
+        match self {
+            SignatureScheme::Ed25519 => 32,
+            SignatureScheme::Secp256k1 | SignatureScheme::Secp256r1 => 33,
+        }
+    }
+}
+
+/**
+ * Length in bytes of a raw signature, the same for every scheme
+ */
+const SIGNATURE_LENGTH: usize = 64;
+
+/**
+ * A signature together with the public key that produced it.
+ * Renders to the exchange wire format `hex(signature) || scheme suffix || base64(public key)` (Ed25519 and secp256k1)
+ * or the standard Sui format `base64(flag || signature || public key)`, and parses back from either
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BluefinSuiSignature {
+    scheme: SignatureScheme,
+    signature: Vec<u8>,
+    public_key: Vec<u8>,
+}
+
+impl BluefinSuiSignature {
+    pub fn new(scheme: SignatureScheme, signature: Vec<u8>, public_key: Vec<u8>) -> Result<Self> {
+        if signature.len() != SIGNATURE_LENGTH {
+            return Err(SigningError::InvalidInput(format!(
+                "signature must be {} bytes, got {}",
+                SIGNATURE_LENGTH,
+                signature.len()
+            )));
+        }
+        if public_key.len() != scheme.public_key_length() {
+            return Err(SigningError::InvalidInput(format!(
+                "{:?} public key must be {} bytes, got {}",
+                scheme,
+                scheme.public_key_length(),
+                public_key.len()
+            )));
+        }
+        Ok(BluefinSuiSignature {
+            scheme,
+            signature,
+            public_key,
+        })
+    }
+
+    pub fn from_ed25519(signature: &Signature, public_key: &VerifyingKey) -> Self {
+        BluefinSuiSignature {
+            scheme: SignatureScheme::Ed25519,
+            signature: signature.to_bytes().to_vec(),
+            public_key: public_key.to_bytes().to_vec(),
+        }
+    }
+
+    pub fn scheme(&self) -> SignatureScheme {
+        self.scheme
+    }
+
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    /**
+     * Returns the address of the account whose public key is embedded in the signature
+     */
+    pub fn signer_address(&self) -> SuiAddress {
+        SuiAddress::from_public_key(self.scheme, &self.public_key).expect("public key length is checked on construction")
+    }
+
+    /**
+     * Returns true if the signature is valid for the message under the embedded public key.
+     * Secp256k1 and secp256r1 signatures are checked against the SHA-256 of the message, like they are signed
+     */
+    pub fn verify(&self, message: &[u8]) -> bool {
+        match self.scheme {
+            SignatureScheme::Ed25519 => {
+                let public_key: [u8; 32] = match self.public_key.as_slice().try_into() {
+                    Ok(public_key) => public_key,
+                    Err(_) => return false,
+                };
+                let signature = match Signature::from_slice(&self.signature) {
+                    Ok(signature) => signature,
+                    Err(_) => return false,
+                };
+                VerifyingKey::from_bytes(&public_key)
+                    .map(|key| key.verify(message, &signature).is_ok())
+                    .unwrap_or(false)
+            }
+            SignatureScheme::Secp256k1 => {
+                match (
+                    k256::ecdsa::VerifyingKey::from_sec1_bytes(&self.public_key),
+                    k256::ecdsa::Signature::from_slice(&self.signature),
+                ) {
+                    (Ok(key), Ok(signature)) => key.verify(message, &signature).is_ok(),
+                    _ => false,
+                }
+            }
+            SignatureScheme::Secp256r1 => {
+                match (
+                    p256::ecdsa::VerifyingKey::from_sec1_bytes(&self.public_key),
+                    p256::ecdsa::Signature::from_slice(&self.signature),
+                ) {
+                    (Ok(key), Ok(signature)) => key.verify(message, &signature).is_ok(),
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    /**
+     * Returns the signature in the format expected by the exchange, which secp256r1 signatures do not have
+     */
+    pub fn to_wire_format(&self) -> Result<String> {
+        Ok(format!(
+            "{}{}{}",
+            hex::encode(&self.signature),
+            self.scheme.wire_suffix()?,
+            BASE64.encode(&self.public_key)
+        ))
+    }
+
+    pub fn from_wire_format(wire: &str) -> Result<Self> {
+        let invalid = || SigningError::InvalidInput(format!("malformed wire signature: {}", wire));
+        let signature_hex = wire.get(..SIGNATURE_LENGTH * 2).ok_or_else(invalid)?;
+        let suffix = wire[SIGNATURE_LENGTH * 2..].chars().next().ok_or_else(invalid)?;
+        let public_key_b64 = &wire[SIGNATURE_LENGTH * 2 + suffix.len_utf8()..];
+
+        let signature = hex::decode(signature_hex).map_err(|_| invalid())?;
+        let public_key = BASE64.decode(public_key_b64).map_err(|_| invalid())?;
+        BluefinSuiSignature::new(SignatureScheme::from_wire_suffix(suffix)?, signature, public_key)
+    }
+
+    /**
+     * Returns the signature in the standard Sui serialized format
+     */
+    pub fn to_sui_format(&self) -> String {
+        let mut bytes = Vec::with_capacity(1 + self.signature.len() + self.public_key.len());
+        bytes.push(self.scheme.flag());
+        bytes.extend_from_slice(&self.signature);
+        bytes.extend_from_slice(&self.public_key);
+        BASE64.encode(bytes)
+    }
+
+    pub fn from_sui_format(serialized: &str) -> Result<Self> {
+        let bytes = BASE64
+            .decode(serialized)
+            .map_err(|e| SigningError::InvalidInput(format!("malformed sui signature: {}", e)))?;
+        let (flag, rest) = bytes
+            .split_first()
+            .ok_or_else(|| SigningError::InvalidInput("empty sui signature".to_string()))?;
+        if rest.len() < SIGNATURE_LENGTH {
+            return Err(SigningError::InvalidInput("sui signature is too short".to_string()));
+        }
+        let (signature, public_key) = rest.split_at(SIGNATURE_LENGTH);
+        BluefinSuiSignature::new(SignatureScheme::from_flag(*flag)?, signature.to_vec(), public_key.to_vec())
+    }
+}
+
+/**
+ * Displays the exchange wire format, or the standard Sui format for schemes the wire format does not carry
+ */
+impl fmt::Display for BluefinSuiSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_wire_format() {
+            Ok(wire) => f.write_str(&wire),
+            Err(_) => f.write_str(&self.to_sui_format()),
+        }
+    }
+}
+
+/**
+ * Parses either the exchange wire format or the standard Sui format
+ */
+impl FromStr for BluefinSuiSignature {
+    type Err = SigningError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let is_wire_format = value
+            .get(..SIGNATURE_LENGTH * 2)
+            .map(|prefix| prefix.bytes().all(|b| b.is_ascii_hexdigit()))
+            .unwrap_or(false);
+
+        if is_wire_format {
+            BluefinSuiSignature::from_wire_format(value)
+        } else {
+            BluefinSuiSignature::from_sui_format(value)
+        }
+    }
+}
+
+/**
+ * Returns true if the order signature is valid and its public key derives to the order maker
+ */
+pub fn verify_sui_order_signature(order: &Order, signature: &BluefinSuiSignature, markets: &MarketRegistry) -> Result<bool> {
+    if signature.signer_address() != order.maker {
+        return Ok(false);
+    }
+    let serialized_order = get_serialized_order(order, markets)?;
+    Ok(signature.verify(&get_order_signing_digest(&serialized_order)?))
+}
+
+/**
+ * Returns true if the cancel signature of the order hashes is valid and was made by the given account
+ */
+pub fn verify_sui_cancel_signature(order_hashes: &[&str], signature: &BluefinSuiSignature, signer: &SuiAddress) -> bool {
+    signature.signer_address() == *signer
+        && SuiPersonalMessageSigner::verify_bytes(&get_cancel_message(order_hashes), signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sui::order::{sign_cancel_orders, sign_order};
+    use crate::sui::{OrderType, Side, SuiKeyPair, TimeInForce};
+
+    const WALLET_KEY: &str = "c501312ca9eb1aaac6344edbe160e41d3d8d79570e6440f2a84f7d9abf462270";
+
+    const SCHEMES: [SignatureScheme; 3] = [SignatureScheme::Ed25519, SignatureScheme::Secp256k1, SignatureScheme::Secp256r1];
+
+    fn keypair(scheme: SignatureScheme) -> SuiKeyPair {
+        SuiKeyPair::from_hex(scheme, WALLET_KEY).unwrap()
+    }
+
+    #[test]
+    fn ed25519_signatures_round_trip_through_both_formats() {
+        let signature = keypair(SignatureScheme::Ed25519).sign(b"message");
+        let wire = signature.to_wire_format().unwrap();
+        assert_eq!(wire.chars().nth(128), Some('1'));
+        assert_eq!(wire, signature.to_string());
+        assert_eq!(wire.parse::<BluefinSuiSignature>().unwrap(), signature);
+        assert_eq!(signature.to_sui_format().parse::<BluefinSuiSignature>().unwrap(), signature);
+    }
+
+    #[test]
+    fn secp256k1_signatures_round_trip_through_both_formats() {
+        let signature = keypair(SignatureScheme::Secp256k1).sign(b"message");
+        let wire = signature.to_wire_format().unwrap();
+        assert_eq!(wire.chars().nth(128), Some('2'));
+        assert_eq!(wire, signature.to_string());
+        assert_eq!(BluefinSuiSignature::from_wire_format(&wire).unwrap(), signature);
+        assert_eq!(wire.parse::<BluefinSuiSignature>().unwrap(), signature);
+        assert_eq!(signature.to_sui_format().parse::<BluefinSuiSignature>().unwrap(), signature);
+        assert!(BluefinSuiSignature::from_wire_format(&wire).unwrap().verify(b"message"));
+    }
+
+    #[test]
+    fn secp256r1_signatures_have_no_wire_format() {
+        let signature = keypair(SignatureScheme::Secp256r1).sign(b"message");
+        assert!(matches!(signature.to_wire_format(), Err(SigningError::InvalidInput(_))));
+        assert_eq!(signature.to_string(), signature.to_sui_format());
+        assert_eq!(signature.to_string().parse::<BluefinSuiSignature>().unwrap(), signature);
+        assert!(SignatureScheme::from_wire_suffix('0').is_err());
+        assert!(SignatureScheme::from_wire_suffix('3').is_err());
+    }
+
+    #[test]
+    fn rejects_wire_signatures_whose_key_does_not_match_the_signer_type() {
+        let ed25519 = keypair(SignatureScheme::Ed25519).sign(b"message").to_wire_format().unwrap();
+        let secp256k1 = keypair(SignatureScheme::Secp256k1).sign(b"message").to_wire_format().unwrap();
+        let swapped = format!("{}2{}", &ed25519[..128], &ed25519[129..]);
+        assert!(BluefinSuiSignature::from_wire_format(&swapped).is_err());
+        let swapped = format!("{}1{}", &secp256k1[..128], &secp256k1[129..]);
+        assert!(BluefinSuiSignature::from_wire_format(&swapped).is_err());
+    }
+
+    fn markets() -> MarketRegistry {
+        let mut markets = MarketRegistry::new();
+        markets.insert("ETH-PERP", "0x3".parse().unwrap());
+        markets
+    }
+
+    fn order(maker: SuiAddress) -> Order {
+        Order {
+            market: "ETH-PERP".to_string(),
+            price: 1_800_000_000_000_000_000_000,
+            trigger_price: 0,
+            side: Side::Buy,
+            reduce_only: false,
+            quantity: 10_000_000_000_000_000,
+            post_only: false,
+            orderbook_only: true,
+            leverage: 3_000_000_000_000_000_000,
+            expiration: 1696489993397,
+            salt: 1695466663327515,
+            maker,
+            ioc: false,
+            order_type: OrderType::Limit,
+            time_in_force: TimeInForce::Gtt,
+        }
+    }
+
+    fn signed_order(keypair: &SuiKeyPair) -> (Order, BluefinSuiSignature) {
+        let order = order(keypair.address());
+        let signature = sign_order(keypair, &get_serialized_order(&order, &markets()).unwrap()).unwrap();
+        (order, signature)
+    }
+
+    #[test]
+    fn verifies_order_signatures_of_every_scheme() {
+        for scheme in SCHEMES {
+            let (order, signature) = signed_order(&keypair(scheme));
+            assert!(verify_sui_order_signature(&order, &signature, &markets()).unwrap(), "{:?}", scheme);
+        }
+    }
+
+    #[test]
+    fn rejects_tampered_orders() {
+        for scheme in SCHEMES {
+            let (mut order, signature) = signed_order(&keypair(scheme));
+            order.quantity += 1;
+            assert!(!verify_sui_order_signature(&order, &signature, &markets()).unwrap(), "{:?}", scheme);
+        }
+    }
+
+    #[test]
+    fn rejects_orders_signed_by_someone_else_than_the_maker() {
+        let (order, _) = signed_order(&keypair(SignatureScheme::Ed25519));
+        let serialized_order = get_serialized_order(&order, &markets()).unwrap();
+        let signature = sign_order(&keypair(SignatureScheme::Secp256k1), &serialized_order).unwrap();
+        assert!(signature.verify(&get_order_signing_digest(&serialized_order).unwrap()));
+        assert!(!verify_sui_order_signature(&order, &signature, &markets()).unwrap());
+    }
+
+    #[test]
+    fn verifies_cancel_signatures() {
+        let order_hashes = ["8f4350a82739c4c5d6528dae76df99da64fc68084248786376cd1dbcafddd91b", "01"];
+        for scheme in SCHEMES {
+            let signer = keypair(scheme);
+            let stranger = keypair(if scheme == SignatureScheme::Ed25519 { SignatureScheme::Secp256k1 } else { SignatureScheme::Ed25519 });
+            let signature = sign_cancel_orders(&signer, order_hashes.to_vec());
+            assert!(verify_sui_cancel_signature(&order_hashes, &signature, &signer.address()), "{:?}", scheme);
+            assert!(!verify_sui_cancel_signature(&order_hashes[..1], &signature, &signer.address()));
+            assert!(!verify_sui_cancel_signature(&order_hashes, &signature, &stranger.address()));
+        }
+    }
+
+    #[test]
+    fn sub_account_orders_keep_the_parent_as_maker() {
+        // the exchange checks the on-chain sub-account setting of the maker, the signature itself is the sub-account's
+        let parent = keypair(SignatureScheme::Ed25519);
+        let sub_account = keypair(SignatureScheme::Secp256k1);
+        let order = order(parent.address());
+        let serialized_order = get_serialized_order(&order, &markets()).unwrap();
+        let signature = sign_order(&sub_account, &serialized_order).unwrap();
+
+        assert_eq!(signature.signer_address(), sub_account.address());
+        assert!(signature.verify(&get_order_signing_digest(&serialized_order).unwrap()));
+        assert!(!verify_sui_order_signature(&order, &signature, &markets()).unwrap());
+    }
+}
@@ -0,0 +1,593 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::api::{AuthorizeResponse, CancelOrdersResponse, OrderResponse};
+use crate::{Environment, Result, SigningError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+/**
+ * A request to the exchange REST API, independent of the HTTP library that sends it
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    /// JSON body
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    pub fn new(method: HttpMethod, url: String) -> Self {
+        HttpRequest {
+            method,
+            url,
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    pub fn query(mut self, name: &str, value: &str) -> Self {
+        self.query.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn bearer(self, jwt_token: &str) -> Self {
+        self.header("Authorization", &format!("Bearer {}", jwt_token))
+    }
+
+    pub fn json<T: Serialize>(mut self, body: &T) -> Result<Self> {
+        self.body = Some(serde_json::to_string(body)?);
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        HttpResponse {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /**
+     * Returns the first value of a header, matching its name case insensitively
+     */
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/**
+ * Sends requests to the exchange. `ReqwestTransport` talks to the network,
+ * `MockTransport` answers in process so clients can be exercised without an exchange
+ */
+pub trait Transport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> impl Future<Output = Result<HttpResponse>> + Send;
+}
+
+/**
+ * Time allowed to open a connection to the exchange by `ReqwestTransport::default`
+ */
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/**
+ * Time allowed for a whole request, response included, by `ReqwestTransport::default`
+ */
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/**
+ * Transport over a reqwest client. Clones share the client and with it the connection pool
+ */
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        ReqwestTransport { client }
+    }
+
+    pub fn with_timeouts(connect_timeout: Duration, request_timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(connect_timeout)
+            .timeout(request_timeout)
+            .build()?;
+        Ok(ReqwestTransport::new(client))
+    }
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        // fails only if the TLS backend can not be initialized, where reqwest::Client::new panics as well
+        ReqwestTransport::with_timeouts(DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT)
+            .expect("reqwest client can not be built")
+    }
+}
+
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let method = match request.method {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Put => reqwest::Method::PUT,
+            HttpMethod::Delete => reqwest::Method::DELETE,
+        };
+        let mut builder = self.client.request(method, &request.url).query(&request.query);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.header(reqwest::header::CONTENT_TYPE, "application/json").body(body);
+        }
+
+        let res = builder.send().await?;
+        let status = res.status().as_u16();
+        let headers = res
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = res.text().await?;
+        Ok(HttpResponse { status, headers, body })
+    }
+}
+
+type MockHandler = dyn Fn(&HttpRequest) -> HttpResponse + Send + Sync;
+
+/**
+ * In process stand-in for the exchange: every request is recorded and answered by the handler
+ */
+#[derive(Clone)]
+pub struct MockTransport {
+    handler: Arc<MockHandler>,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl MockTransport {
+    pub fn new(handler: impl Fn(&HttpRequest) -> HttpResponse + Send + Sync + 'static) -> Self {
+        MockTransport {
+            handler: Arc::new(handler),
+            requests: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /**
+     * Returns the requests received so far, oldest first
+     */
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().expect("mock transport lock poisoned").clone()
+    }
+}
+
+impl fmt::Debug for MockTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockTransport")
+            .field("requests", &self.requests())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Transport for MockTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let response = (self.handler)(&request);
+        self.requests.lock().expect("mock transport lock poisoned").push(request);
+        Ok(response)
+    }
+}
+
+/**
+ * When and how often a request is sent again. Only idempotent requests may be retried:
+ * a retried request can reach the exchange twice
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// retries after the first attempt, 0 disables retrying
+    pub max_retries: u32,
+    /// backoff before the first retry, doubled for each one after
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// a `Retry-After` asking to wait longer than this is not waited for, the response is returned instead
+    pub max_retry_after: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(8),
+            max_retry_after: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /**
+     * Returns true for responses that may succeed when sent again: rate limited (429) or
+     * a temporary server side failure (500, 502, 503, 504)
+     */
+    pub fn is_retryable_status(status: u16) -> bool {
+        matches!(status, 429 | 500 | 502 | 503 | 504)
+    }
+
+    /**
+     * Returns true for transport failures that may succeed when sent again: timeouts and failed connections
+     */
+    pub fn is_retryable_error(error: &SigningError) -> bool {
+        match error {
+            SigningError::Transport(error) => error.is_timeout() || error.is_connect(),
+            _ => false,
+        }
+    }
+
+    /**
+     * Exponential backoff before retry number `retry` (0 based), between half and all of the
+     * doubled backoff so clients that failed together do not retry together
+     */
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .checked_mul(2u32.saturating_pow(retry))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        backoff / 2 + backoff.mul_f64(random_fraction() / 2.0)
+    }
+
+    /**
+     * Sends the request, sending it again on retryable failures after the `Retry-After` of the
+     * response or the backoff. Returns the last response or error once retries run out
+     */
+    pub async fn send<T: Transport>(&self, transport: &T, request: HttpRequest) -> Result<HttpResponse> {
+        let mut retry = 0;
+        loop {
+            let result = transport.send(request.clone()).await;
+            if retry >= self.max_retries {
+                return result;
+            }
+
+            let delay = match &result {
+                Ok(response) if RetryPolicy::is_retryable_status(response.status) => {
+                    match get_retry_after(response) {
+                        Some(retry_after) if retry_after > self.max_retry_after => return result,
+                        Some(retry_after) => retry_after,
+                        None => self.backoff(retry),
+                    }
+                }
+                Err(error) if RetryPolicy::is_retryable_error(error) => self.backoff(retry),
+                _ => return result,
+            };
+            tokio::time::sleep(delay).await;
+            retry += 1;
+        }
+    }
+}
+
+/**
+ * Reads the `Retry-After` header given in seconds, the HTTP date form is ignored
+ */
+fn get_retry_after(response: &HttpResponse) -> Option<Duration> {
+    response
+        .header("Retry-After")
+        .and_then(|retry_after| retry_after.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+/**
+ * Returns a value in [0, 1] for jitter, randomness from the std hasher keys is plenty for that
+ */
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos());
+    hasher.finish() as f64 / u64::MAX as f64
+}
+
+/**
+ * Turns an error response into `SigningError::Exchange`, reading the code and message the exchange
+ * puts in its JSON error bodies (`{"error": {"code", "message"}}` or `{"code", "message"}`)
+ */
+pub fn get_exchange_error(response: &HttpResponse) -> SigningError {
+    let body: Option<Value> = serde_json::from_str(&response.body).ok();
+    let error = body.as_ref().map(|body| body.get("error").unwrap_or(body));
+
+    let code = error.and_then(|error| error.get("code")).and_then(Value::as_i64);
+    let message = error
+        .and_then(|error| error.get("message"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| response.body.clone());
+
+    SigningError::Exchange {
+        status: response.status,
+        code,
+        message,
+    }
+}
+
+/**
+ * Decodes the JSON body of a successful response, or returns the exchange error
+ */
+pub fn decode_response<T: DeserializeOwned>(response: HttpResponse) -> Result<T> {
+    if !response.is_success() {
+        return Err(get_exchange_error(&response));
+    }
+    serde_json::from_str(&response.body).map_err(|e| SigningError::Exchange {
+        status: response.status,
+        code: None,
+        message: format!("unexpected response {}: {}", response.body, e),
+    })
+}
+
+/**
+ * What the exchange clients have in common: the environment whose API is called, the transport
+ * shared by every request, the retry policy applied to idempotent requests, and the routes both
+ * deployments answer alike. The request bodies differ between deployments and are passed in
+ */
+#[derive(Debug, Clone)]
+pub struct RestClient<T: Transport = ReqwestTransport> {
+    environment: Environment,
+    transport: T,
+    retry_policy: RetryPolicy,
+}
+
+impl RestClient {
+    pub fn new(environment: Environment) -> Self {
+        RestClient::with_transport(environment, ReqwestTransport::default())
+    }
+}
+
+impl<T: Transport> RestClient<T> {
+    pub fn with_transport(environment: Environment, transport: T) -> Self {
+        RestClient {
+            environment,
+            transport,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /**
+     * Starts a request to a route of the environment API, such as "/orders"
+     */
+    pub fn request(&self, method: HttpMethod, route: &str) -> HttpRequest {
+        HttpRequest::new(method, self.environment.api_route(route))
+    }
+
+    /**
+     * Sends the request once and decodes the response
+     */
+    pub async fn send<R: DeserializeOwned>(&self, request: HttpRequest) -> Result<R> {
+        decode_response(self.transport.send(request).await?)
+    }
+
+    /**
+     * Sends the request, retrying by the retry policy, and decodes the response. Only for idempotent requests
+     */
+    pub async fn send_idempotent<R: DeserializeOwned>(&self, request: HttpRequest) -> Result<R> {
+        decode_response(self.retry_policy.send(&self.transport, request).await?)
+    }
+
+    /**
+     * POSTS the onboarding signature request and returns the JWT token
+     */
+    pub async fn authorize<B: Serialize>(&self, authorize_request: &B) -> Result<String> {
+        let request = self.request(HttpMethod::Post, "/authorize").json(authorize_request)?;
+        let response: AuthorizeResponse = self.send_idempotent(request).await?;
+        Ok(response.token)
+    }
+
+    /**
+     * POSTS the signed order once. A timeout or a 5xx does not tell whether the order was placed,
+     * so it is not retried
+     */
+    pub async fn post_order<B: Serialize>(&self, order_request: &B, jwt_token: &str) -> Result<OrderResponse> {
+        let request = self.request(HttpMethod::Post, "/orders").bearer(jwt_token).json(order_request)?;
+        self.send(request).await
+    }
+
+    /**
+     * POSTS the signed order, retrying by the retry policy. Only the identical signed request is sent again:
+     * same salt, hence the same order hash, so a retry can not place a second order next to the first.
+     * Never re-sign with a new salt after a failure without checking whether the first order was placed
+     */
+    pub async fn post_order_with_retry<B: Serialize>(&self, order_request: &B, jwt_token: &str) -> Result<OrderResponse> {
+        let request = self.request(HttpMethod::Post, "/orders").bearer(jwt_token).json(order_request)?;
+        self.send_idempotent(request).await
+    }
+
+    /**
+     * Sends a signed cancellation request, retrying by the retry policy
+     */
+    pub async fn cancel_orders<B: Serialize>(&self, order_cancel: &B, jwt_token: &str) -> Result<CancelOrdersResponse> {
+        let request = self
+            .request(HttpMethod::Delete, "/orders/hash")
+            .bearer(jwt_token)
+            .json(order_cancel)?;
+        self.send_idempotent(request).await
+    }
+
+    /**
+     * Sends each cancellation request in turn, as made by `create_cancel_requests`, stopping at the first failure
+     */
+    pub async fn cancel_orders_in_chunks<B: Serialize>(
+        &self,
+        order_cancels: &[B],
+        jwt_token: &str,
+    ) -> Result<Vec<CancelOrdersResponse>> {
+        let mut responses = Vec::with_capacity(order_cancels.len());
+        for order_cancel in order_cancels {
+            responses.push(self.cancel_orders(order_cancel, jwt_token).await?);
+        }
+        Ok(responses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /**
+     * Transport answering with the given responses in turn, repeating the last one
+     */
+    fn transport(responses: Vec<HttpResponse>) -> MockTransport {
+        let sent = AtomicUsize::new(0);
+        MockTransport::new(move |_| {
+            let index = sent.fetch_add(1, Ordering::SeqCst).min(responses.len() - 1);
+            responses[index].clone()
+        })
+    }
+
+    fn retry_after(status: u16, seconds: &str) -> HttpResponse {
+        let mut response = HttpResponse::new(status, "");
+        response.headers.push(("Retry-After".to_string(), seconds.to_string()));
+        response
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        }
+    }
+
+    fn request() -> HttpRequest {
+        HttpRequest::new(HttpMethod::Delete, "https://exchange/orders/hash".to_string())
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limits_and_server_errors() {
+        let transport = transport(vec![
+            HttpResponse::new(429, ""),
+            HttpResponse::new(503, ""),
+            HttpResponse::new(200, "{}"),
+        ]);
+
+        assert_eq!(policy().send(&transport, request()).await.unwrap().status, 200);
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|sent| *sent == request()));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_the_last_retry() {
+        let transport = transport(vec![HttpResponse::new(502, "")]);
+
+        assert_eq!(policy().send(&transport, request()).await.unwrap().status, 502);
+        assert_eq!(transport.requests().len(), 1 + policy().max_retries as usize);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_other_statuses_or_when_disabled() {
+        for status in [200, 400, 401, 404, 501] {
+            let transport = transport(vec![HttpResponse::new(status, "")]);
+            assert_eq!(policy().send(&transport, request()).await.unwrap().status, status);
+            assert_eq!(transport.requests().len(), 1, "{}", status);
+        }
+
+        let transport = transport(vec![HttpResponse::new(503, "")]);
+        assert_eq!(RetryPolicy::none().send(&transport, request()).await.unwrap().status, 503);
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_for_retry_after() {
+        let transport = transport(vec![retry_after(429, " 7 "), HttpResponse::new(200, "")]);
+
+        let start = tokio::time::Instant::now();
+        assert_eq!(policy().send(&transport, request()).await.unwrap().status, 200);
+        assert!(start.elapsed() >= Duration::from_secs(7));
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn returns_the_response_when_retry_after_exceeds_the_cap() {
+        let transport = transport(vec![retry_after(503, "61"), HttpResponse::new(200, "")]);
+
+        let start = tokio::time::Instant::now();
+        assert_eq!(policy().send(&transport, request()).await.unwrap().status, 503);
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn reads_retry_after_seconds_only() {
+        assert_eq!(get_retry_after(&retry_after(429, "120")), Some(Duration::from_secs(120)));
+        assert_eq!(get_retry_after(&retry_after(429, "Wed, 21 Oct 2015 07:28:00 GMT")), None);
+        assert_eq!(get_retry_after(&HttpResponse::new(429, "")), None);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let policy = RetryPolicy::default();
+        for retry in 0..3 {
+            let doubled = policy.initial_backoff * 2u32.pow(retry);
+            let backoff = policy.backoff(retry);
+            assert!(backoff >= doubled / 2 && backoff <= doubled, "{:?}", backoff);
+        }
+        for retry in [10, 40, u32::MAX] {
+            assert!(policy.backoff(retry) <= policy.max_backoff);
+        }
+    }
+}
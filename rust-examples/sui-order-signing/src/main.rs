@@ -1,104 +1,86 @@
-use ed25519_dalek::*;
+use bluefin_signing::sui::{
+    api, order, verify_sui_order_signature, AuthSession, BluefinClient, MarketRegistry, OrderType, Side, SignatureScheme, SuiKeyPair, TimeInForce,
+};
+use bluefin_signing::Environment;
 use web3_unit_converter::Unit;
-use blake2b_simd::Params;
-use sha256::digest;
-
-mod order;
-
 
 #[tokio::main]
-async fn main() {
+async fn main() -> bluefin_signing::Result<()> {
     let wallet_key = "c501312ca9eb1aaac6344edbe160e41d3d8d79570e6440f2a84f7d9abf462270";
 
-    // JWT Token obtained from onboarding signer corresponding to the same wallet
-    let jwt_token = "<JWT from onboarding signer example>";
+    // Sui staging unless BLUEFIN_ENV (e.g. sui-mainnet) or BLUEFIN_CONFIG (a JSON file) say otherwise
+    let environment = Environment::from_env(Environment::sui_staging())?;
+
+    // one client for every request, so the connection is reused
+    let client = BluefinClient::new(environment);
 
     // Market: ETH-PERP or BTC-PERP
     let market = "ETH-PERP";
 
-    // Decode Private Key Hex String to Bytes
-    let bytes = hex::decode(wallet_key).expect("Decoding failed");
-    let mut private_key_bytes: [u8; 32] = [0; 32];
-    private_key_bytes.copy_from_slice(&bytes[0..32]);
-
-    // Convert to Signing Key
-    let signingkey = SigningKey::from_bytes(&private_key_bytes);
-
-    // Generate the corresponding public key
-    let public_key: VerifyingKey = (&signingkey).into();
-    // println!("Public key bytes: {:?}", public_key.to_bytes());
-
-    // Generate the b64 of the public key
-    let public_key_b64 = base64::encode(&public_key.to_bytes());
-    // println!("Public Key Base64: {}", public_key_b64);
-
-    // Append 0x00 to public key due to BIP32
-    let public_key_array = public_key.to_bytes();
-    let mut public_key_array_bip32 = [0; 33];
-    public_key_array_bip32[0] = 0;
-    public_key_array_bip32[1..].copy_from_slice(&public_key_array);
-    // println!("PUBLIC KEY ARRAY BIP32 {:?}", public_key_array_bip32);
-    
-    // Generate Wallet Address for BIP32 Public Key
-    let hash = Params::new()
-        .hash_length(32)
-        .to_state()
-        .update(&public_key_array_bip32)
-        .finalize();
-    let wallet_address = "0x".to_string() + &hash.to_hex().to_ascii_lowercase();
+    // Perpetual ids of the markets, fetched once here. Use MarketRegistry::load with a
+    // JSON file like {"ETH-PERP": "0x..."} to hash orders without any network access
+    let mut markets = MarketRegistry::new();
+    markets.refresh(&client, market).await?;
+
+    // Convert to Signing Key, use SignatureScheme::Secp256k1 for those accounts. Secp256r1 keys derive
+    // addresses and sign too, but the exchange has no signer type for their signatures
+    let keypair = SuiKeyPair::from_hex(SignatureScheme::Ed25519, wallet_key)?;
+
+    // Onboards on the first request and again whenever the JWT token expires,
+    // the token is kept in a file so the next run reuses it
+    let session = AuthSession::new(client, keypair.clone()).with_token_file("bluefin-token.json");
+
+    // Generate Wallet Address from the corresponding public key
+    let wallet_address = keypair.address();
     println!("Wallet Address: {}", wallet_address);
 
     // Create an Order
     let order = order::Order{
         market: market.to_string(),
-        isBuy: true,
-        price: (Unit::Ether(&"0").to_wei_str().unwrap()).parse().unwrap() ,
-        quantity: (Unit::Ether(&"0.01").to_wei_str().unwrap()).parse().unwrap(),
-        leverage: (Unit::Ether(&"3").to_wei_str().unwrap()).parse().unwrap(),
-        maker: wallet_address.to_string(),
-        reduceOnly: false,
-        postOnly: false,
-        orderbookOnly: true,
+        side: Side::Buy,
+        price: (Unit::Ether("0").to_wei_str().unwrap()).parse().unwrap() ,
+        trigger_price: 0, // only set for STOP_MARKET and STOP_LIMIT orders, it is not part of the signed order
+        quantity: (Unit::Ether("0.01").to_wei_str().unwrap()).parse().unwrap(),
+        leverage: (Unit::Ether("3").to_wei_str().unwrap()).parse().unwrap(),
+        maker: wallet_address,
+        reduce_only: false,
+        post_only: false,
+        orderbook_only: true,
         expiration: 1696489993397,
         salt: 1695466663327515,
         ioc: false,
-        orderType: "MARKET".to_string(),
-        timeInForce: "GTT".to_string()
+        order_type: OrderType::Market,
+        time_in_force: TimeInForce::Gtt
     };
 
     // Generate Order Hash, Sign, append "1" and append the base64 of the public key
-    let serialized_msg = order::get_serialized_order(&order).await;
-    let order_hash = digest(hex::decode(&serialized_msg).expect("Decoding failed"));
+    let serialized_msg = order::get_serialized_order(&order, &markets)?;
+    let order_hash = order::get_order_hash(&serialized_msg)?;
     println!("Order Hash: {}", order_hash);
 
-    let msg_hash_decoded = hex::decode(digest(&serialized_msg)).expect("Decoding failed");
-    let msg_hash_sig  = signingkey.sign(&msg_hash_decoded);
-    let msg_hash_sig = msg_hash_sig.to_string().to_ascii_lowercase() + "1" + &public_key_b64;
+    let msg_hash_sig = order::sign_order(&keypair, &serialized_msg)?;
+
+    // the exchange checks that the order was signed by its maker
+    assert!(verify_sui_order_signature(&order, &msg_hash_sig, &markets)?);
 
     // Post Order and return the order hash
-    let returned_order_hash = order::post_signed_order(&order,msg_hash_sig, jwt_token).await;
+    let returned_order_hash = session.post_signed_order(&order, &msg_hash_sig).await?;
     println!("Returned Order Hash: {}", returned_order_hash);
 
     // order hash generated should be the same as the one returned
     assert_eq!(returned_order_hash, order_hash);
 
     // Hash the order hashes that require cancellation and sign them
-    let hash = order::create_signed_cancel_order(&order_hash);
-    let cancel_sig_temp  = signingkey.sign(&hash.as_bytes());
-    let cancel_sig = cancel_sig_temp.to_string().to_ascii_lowercase() + "1";
-    println!("Signature: {}", cancel_sig);
-
-    // Combine Onboarding Signature and base64 of Public Key
-    let cancel_sig_full = cancel_sig + &public_key_b64;
+    let cancel_sig_full = order::sign_cancel_orders(&keypair, vec![&order_hash]);
     println!("Full Signature: {}", cancel_sig_full);
 
-    let cancel_order = order::OrderCancellationJSONRequest {
-        symbol : market.to_string(),
-        orderHashes : [order_hash],
-        cancelSignature : cancel_sig_full,
-        parentAddress: "".to_string()
-    };
+    // A sub-account trading for a parent signs with its own key, sets the order maker to the parent
+    // and passes Some(&parent_address) here, once the parent registered it as a sub-account on-chain
+    // api::create_cancel_requests signs and splits any number of hashes into several requests
+    let cancel_order = api::OrderCancellationJSONRequest::new(market, &[&order_hash], &cancel_sig_full, None)?;
+
+    let response = session.cancel_orders(&cancel_order).await?;
+    println!("Response: {:?}", response);
 
-    let response = order::post_cancel_order(cancel_order, jwt_token).await;
-    println!("Response: {}", response);
-}
\ No newline at end of file
+    Ok(())
+}